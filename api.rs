@@ -0,0 +1,66 @@
+//! Contract-agnostic view of the token
+//!
+//! `SimpleTokenApi` covers the core messages a dependent contract needs. It is
+//! implemented by `SimpleTokenRef` for on-chain use and, under `std`, by
+//! `mock::MockSimpleToken` for off-chain unit tests, so integration logic can
+//! be written once against the trait.
+
+use crate::{Error, SimpleTokenRef, TokenBalance};
+use ink::primitives::AccountId;
+
+/// Core token operations, as seen by a calling contract
+pub trait SimpleTokenApi {
+    /// Total supply
+    fn total_supply(&self) -> TokenBalance;
+
+    /// Balance of an account
+    fn balance_of(&self, owner: AccountId) -> TokenBalance;
+
+    /// Allowance granted by `owner` to `spender`
+    fn allowance(&self, owner: AccountId, spender: AccountId) -> TokenBalance;
+
+    /// Transfer from the caller
+    fn transfer(&mut self, to: AccountId, amount: TokenBalance) -> Result<(), Error>;
+
+    /// Approve `spender` for `amount` on behalf of the caller
+    fn approve(&mut self, spender: AccountId, amount: TokenBalance) -> Result<(), Error>;
+
+    /// Transfer using the caller's allowance on `from`
+    fn transfer_from(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        amount: TokenBalance,
+    ) -> Result<(), Error>;
+}
+
+impl SimpleTokenApi for SimpleTokenRef {
+    fn total_supply(&self) -> TokenBalance {
+        SimpleTokenRef::total_supply(self)
+    }
+
+    fn balance_of(&self, owner: AccountId) -> TokenBalance {
+        SimpleTokenRef::balance_of(self, owner)
+    }
+
+    fn allowance(&self, owner: AccountId, spender: AccountId) -> TokenBalance {
+        SimpleTokenRef::allowance(self, owner, spender)
+    }
+
+    fn transfer(&mut self, to: AccountId, amount: TokenBalance) -> Result<(), Error> {
+        SimpleTokenRef::transfer(self, to, amount)
+    }
+
+    fn approve(&mut self, spender: AccountId, amount: TokenBalance) -> Result<(), Error> {
+        SimpleTokenRef::approve(self, spender, amount)
+    }
+
+    fn transfer_from(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        amount: TokenBalance,
+    ) -> Result<(), Error> {
+        SimpleTokenRef::transfer_from(self, from, to, amount)
+    }
+}
@@ -1,233 +1,8291 @@
 // SPDX-License-Identifier: Apache-2.0
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
+pub mod api;
+pub mod math;
+pub mod psp22;
+#[cfg(feature = "std")]
+pub mod mock;
+#[cfg(feature = "std")]
+pub mod test_utils;
+
+pub use self::simple_token::{Error, SimpleToken, SimpleTokenRef};
+
+/// Token amount type. Every balance, allowance and supply figure uses this
+/// alias; intermediate products go through [`math::U256`].
+pub type TokenBalance = u128;
+
 #[ink::contract]
 mod simple_token {
-    use ink::storage::Mapping;
+    use ink::storage::{Lazy, Mapping};
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::hash::Blake2x256;
     use ink::prelude::{string::String, vec::Vec};
+    use ink::scale::{Compact, Decode};
+    use crate::psp22::{
+        PSP22Burnable, PSP22Error, PSP22Metadata, PSP22Mintable, PSP22ReceiverError, PSP22,
+    };
+    use crate::{math::mul_div, TokenBalance};
+
+    /// Storage fields in declaration order. Must be updated together with
+    /// any change to the `SimpleToken` storage struct.
+    const STORAGE_LAYOUT: &str = "balances:Mapping<AccountId,u128>;\
+        allowances:Mapping<(AccountId,AccountId),u128>;\
+        owner:AccountId;\
+        paused:bool;\
+        blacklist:Mapping<AccountId,bool>;\
+        layout_hash:Lazy<u64>;\
+        donation:Lazy<DonationConfig>;\
+        donation_opt_out:Mapping<AccountId,bool>;\
+        total_donated:Lazy<u128>;\
+        redemption:Lazy<RedemptionConfig>;\
+        boost_collections:Lazy<Vec<(AccountId,u32)>>;\
+        partner_programs:Mapping<u32,u32>;\
+        partner_enrollment:Mapping<AccountId,u32>;\
+        token_migration:Lazy<TokenMigration>;\
+        snapshot_id:Lazy<u32>;\
+        balance_snapshots:Mapping<AccountId,Vec<(u32,u128)>>;\
+        snapshot_airdrops:Mapping<u32,SnapshotAirdrop>;\
+        snapshot_airdrop_count:Lazy<u32>;\
+        snapshot_airdrop_claimed:Mapping<(u32,AccountId),()>;\
+        allowance_spent:Mapping<(AccountId,AccountId),u128>;\
+        account_stats:Mapping<AccountId,AccountStats>;\
+        total_supply:Lazy<u128>;\
+        approval_notifiers:Mapping<AccountId,AccountId>;\
+        blacklist_info:Mapping<AccountId,BlacklistInfo>;\
+        pause_info:Lazy<PauseInfo>;\
+        documents:Mapping<[u8;32],Document>;\
+        document_names:Lazy<Vec<[u8;32]>>;\
+        sub_accounts:Mapping<AccountId,Vec<([u8;32],u128)>>;\
+        sub_accounts_restricted:Mapping<AccountId,bool>;\
+        dead_man_switch:Lazy<Option<DeadManSwitch>>;\
+        inheritance:Mapping<AccountId,Inheritance>;\
+        demurrage:Lazy<Option<Demurrage>>;\
+        demurrage_checkpoints:Mapping<AccountId,u64>;\
+        spending_limits:Mapping<AccountId,SpendingLimit>;\
+        pending_spending_limits:Mapping<AccountId,(SpendingLimit,u64)>;\
+        spending_usage:Mapping<AccountId,(u64,u128)>;\
+        burn_allowances:Mapping<(AccountId,AccountId),u128>;\
+        mint_references:Mapping<[u8;32],()>;\
+        used_mint_vouchers:Mapping<u64,()>;\
+        attestations:Mapping<AccountId,Attestation>;\
+        native_fee:Lazy<Option<NativeFee>>;\
+        permissions:Mapping<(AccountId,MessageSelector),Timestamp>;\
+        approval_limits:Mapping<AccountId,ApprovalLimit>;\
+        pending_approval_limits:Mapping<AccountId,(ApprovalLimit,u64)>;\
+        approval_usage:Mapping<AccountId,(u64,u128)>;\
+        supply_observers:Lazy<Vec<AccountId>>;\
+        balance_observers:Mapping<AccountId,AccountId>;\
+        holds:Mapping<[u8;32],Hold>;\
+        held_balances:Mapping<AccountId,u128>;\
+        hold_operators:Mapping<(AccountId,AccountId),()>;\
+        invoices:Mapping<u32,Invoice>;\
+        invoice_count:Lazy<u32>;\
+        payer_invoices:Mapping<AccountId,Vec<u32>>;\
+        payee_invoices:Mapping<AccountId,Vec<u32>>;\
+        arbiters:Mapping<AccountId,()>;\
+        reversible_transfers:Mapping<u32,ReversibleTransfer>;\
+        reversible_transfer_count:Lazy<u32>;\
+        distribution_jobs:Mapping<u32,DistributionJob>;\
+        distribution_job_count:Lazy<u32>;\
+        distribution_entries:Mapping<(u32,u32),(AccountId,u128)>;\
+        cosign_policies:Mapping<AccountId,CosignPolicy>;\
+        cosigned_transfers:Mapping<u32,CosignedTransfer>;\
+        cosigned_transfer_count:Lazy<u32>;\
+        metadata:Lazy<TokenMetadata>;\
+        max_supply:Lazy<u128>;\
+        roles:Mapping<(u32,AccountId),()>;\
+        permit_nonces:Mapping<AccountId,u64>;\
+        fee_bps:Lazy<u16>;\
+        treasury:Lazy<Option<AccountId>>;\
+        fee_exempt:Mapping<AccountId,()>;\
+        burn_rate_bps:Lazy<u16>;\
+        whitelist:Mapping<AccountId,()>;\
+        restricted_mode:Lazy<bool>;\
+        blacklisted_accounts:Lazy<Vec<AccountId>>;\
+        minting_paused:Lazy<bool>;\
+        burning_paused:Lazy<bool>;\
+        pause_deadline:Lazy<Option<u64>>;\
+        guardian:Lazy<Option<AccountId>>;\
+        vesting:Mapping<AccountId,VestingSchedule>;\
+        staking:Lazy<StakingPool>;\
+        stakes:Mapping<AccountId,Stake>;\
+        delegates:Mapping<AccountId,AccountId>;\
+        vote_checkpoints:Mapping<AccountId,Vec<(u32,u128)>>;\
+        governance:Lazy<GovernanceConfig>;\
+        proposals:Mapping<u32,Proposal>;\
+        proposal_count:Lazy<u32>;\
+        proposal_votes:Mapping<(u32,AccountId),()>;\
+        timelock_delay:Lazy<u64>;\
+        scheduled_actions:Mapping<u32,ScheduledAction>;\
+        scheduled_action_count:Lazy<u32>;\
+        merkle_airdrop:Lazy<Option<MerkleAirdrop>>;\
+        merkle_airdrop_claimed:Mapping<(Hash,Hash),()>;\
+        streams:Mapping<u32,Stream>;\
+        stream_count:Lazy<u32>;\
+        escrows:Mapping<u32,Escrow>;\
+        escrow_count:Lazy<u32>;\
+        upgrades_renounced:Lazy<bool>;\
+        storage_version:Lazy<u32>;\
+        flash_fee_bps:Lazy<u16>;\
+        holders:Mapping<u32,AccountId>;\
+        holder_index:Mapping<AccountId,u32>;\
+        holder_count:Lazy<u32>;\
+        transfer_guards:Lazy<TransferGuards>;\
+        allowance_expiries:Mapping<(AccountId,AccountId),u64>;\
+        operators:Mapping<(AccountId,AccountId),()>;\
+        rate_limited_allowances:Mapping<(AccountId,AccountId),RateLimitedAllowance>;\
+        trusted_forwarder:Lazy<Option<AccountId>>;\
+        meta_sender:Lazy<Option<AccountId>>;\
+        compliance_contract:Lazy<Option<AccountId>>;\
+        kyc_registry:Lazy<Option<AccountId>>;\
+        kyc_threshold:Lazy<u128>;\
+        bridge_mint_caps:Mapping<AccountId,u128>;\
+        bridge_outstanding:Mapping<AccountId,u128>;\
+        cross_chain_routes:Mapping<u32,CrossChainRoute>;\
+        cross_chain_transfer_count:Lazy<u64>;\
+        transfer_hooks:Lazy<Vec<AccountId>>;\
+        dividend_index:Lazy<u128>;\
+        dividend_accounts:Mapping<AccountId,DividendAccount>;\
+        sale:Lazy<Option<Sale>>;\
+        sale_id:Lazy<u32>;\
+        sale_contributions:Mapping<(u32,AccountId),u128>;\
+        faucet:Lazy<Option<FaucetConfig>>;\
+        faucet_claims:Mapping<AccountId,u64>;\
+        buyback_router:Lazy<Option<AccountId>>;\
+        treasury_held:Lazy<u128>;\
+        emission:Lazy<Option<EmissionSchedule>>;\
+        emission_minted_until:Lazy<u32>";
+
+    /// Storage fields of the previous release (0.1.0), the only layout
+    /// `migrate()` accepts to upgrade from.
+    const PREVIOUS_STORAGE_LAYOUT: &str = "balances:Mapping<AccountId,u128>;\
+        allowances:Mapping<(AccountId,AccountId),u128>;\
+        owner:AccountId;\
+        paused:bool;\
+        blacklist:Mapping<AccountId,bool>";
+
+    /// Layout hash of this code
+    pub const STORAGE_LAYOUT_HASH: u64 = layout_hash(STORAGE_LAYOUT);
+
+    /// Layout hash this code expects to find in storage before migrating
+    pub const PREVIOUS_STORAGE_LAYOUT_HASH: u64 = layout_hash(PREVIOUS_STORAGE_LAYOUT);
+
+    /// Storage version of this code. `migrate()` steps storage up to it one
+    /// version at a time; bump it with every layout change and add the step.
+    pub const STORAGE_VERSION: u32 = 1;
+
+    /// Basis points in 100%
+    const BPS_DENOMINATOR: u128 = 10_000;
+
+    /// `bps` basis points of `amount`, saturating if `bps` exceeds 100%
+    fn bps_of(amount: TokenBalance, bps: u16) -> TokenBalance {
+        mul_div(amount, u128::from(bps), BPS_DENOMINATOR).unwrap_or(TokenBalance::MAX)
+    }
+
+    /// Scale of the redemption rate: native units paid per token unit, times 1e9
+    pub const REDEMPTION_RATE_SCALE: u128 = 1_000_000_000;
+
+    /// Scale of the staking reward rate: reward units per staked unit per
+    /// block, times 1e12
+    pub const REWARD_RATE_SCALE: u128 = 1_000_000_000_000;
+
+    /// Scale of the sale rate: token units per native unit, times 1e9
+    pub const SALE_RATE_SCALE: u128 = 1_000_000_000;
+
+    /// Scale of the dividend index: dividends per held token, times 1e18
+    pub const DIVIDEND_MAGNITUDE: u128 = 1_000_000_000_000_000_000;
+
+    /// Reward multiplier meaning "no boost", in basis points
+    pub const NO_BOOST_BPS: u32 = 10_000;
+
+    /// Upper bound on boost collections, each costs a cross-contract call
+    const MAX_BOOST_COLLECTIONS: usize = 8;
+
+    /// `PSP34::balance_of` selector
+    const PSP34_BALANCE_OF: [u8; 4] = [0xcd, 0xe7, 0xe5, 0x5f];
+
+    /// `PSP22::transfer` selector
+    const PSP22_TRANSFER: [u8; 4] = [0xdb, 0x20, 0xf9, 0xf5];
+
+    /// `PSP22::transfer_from` selector
+    const PSP22_TRANSFER_FROM: [u8; 4] = [0x54, 0xb3, 0xc7, 0x6e];
+
+    /// `PSP22Burnable::burn` selector
+    const PSP22_BURN: [u8; 4] = [0x7a, 0x9d, 0xa5, 0x10];
+
+    /// `PSP22Receiver::before_received` selector
+    const PSP22_BEFORE_RECEIVED: [u8; 4] = [0xfd, 0xa6, 0xf1, 0xa9];
+
+    /// `FlashBorrower::on_flash_loan(initiator, amount, fee, data)` selector
+    const ON_FLASH_LOAN: [u8; 4] = [0x38, 0x09, 0x6f, 0xfe];
+
+    /// `ComplianceHook::check_transfer(from, to, amount) -> bool` selector
+    const CHECK_TRANSFER: [u8; 4] = [0xa6, 0x67, 0x55, 0x8c];
+
+    /// `KycRegistry::is_verified(account) -> bool` selector
+    const IS_VERIFIED: [u8; 4] = [0x45, 0x87, 0x45, 0xa0];
+
+    /// `ApprovalObserver::on_allowance_spent(owner, spender, amount)` selector
+    const ON_ALLOWANCE_SPENT: [u8; 4] = [0x38, 0xd3, 0x28, 0x8d];
+
+    /// `SupplyObserver::on_supply_changed(previous_total, new_total)` selector
+    const ON_SUPPLY_CHANGED: [u8; 4] = [0xf3, 0x93, 0xf3, 0x2a];
+
+    /// `BalanceObserver::on_balance_changed(account, previous, new)` selector
+    const ON_BALANCE_CHANGED: [u8; 4] = [0x21, 0x8a, 0x35, 0x92];
+
+    /// Upper bound on supply observers, each costs a notification call
+    const MAX_SUPPLY_OBSERVERS: usize = 8;
+
+    /// `TransferHook::before_transfer(operator, from, to, amount)` selector
+    const BEFORE_TRANSFER: [u8; 4] = [0x8a, 0xbc, 0xa1, 0xeb];
+
+    /// `TransferHook::after_transfer(operator, from, to, amount)` selector
+    const AFTER_TRANSFER: [u8; 4] = [0x29, 0xc8, 0xb3, 0x30];
+
+    /// `BuybackRouter::swap_exact_native_for_tokens(token, min_out, to)` selector,
+    /// payable with the native amount to spend
+    const SWAP_EXACT_NATIVE_FOR_TOKENS: [u8; 4] = [0x93, 0xec, 0x07, 0x8e];
+
+    /// Upper bound on transfer hooks, each costs two calls per balance change
+    const MAX_TRANSFER_HOOKS: usize = 8;
+
+    /// Execution time cap for fire-and-forget notification calls
+    const NOTIFY_REF_TIME_LIMIT: u64 = 5_000_000_000;
+
+    /// Shortest allowed dead-man switch timeout: one day in milliseconds
+    const MIN_DEAD_MAN_TIMEOUT: Timestamp = 24 * 60 * 60 * 1000;
+
+    /// Shortest allowed inheritance inactivity period: 30 days in milliseconds
+    const MIN_INACTIVITY_PERIOD: Timestamp = 30 * 24 * 60 * 60 * 1000;
+
+    /// Delay between starting and finalizing an inheritance claim, during
+    /// which the account holder can cancel: 7 days in milliseconds
+    const INHERITANCE_CHALLENGE_PERIOD: Timestamp = 7 * 24 * 60 * 60 * 1000;
+
+    /// One day in milliseconds
+    const DAY: Timestamp = 24 * 60 * 60 * 1000;
+
+    /// Maximum number of sub-accounts per holder
+    const MAX_SUB_ACCOUNTS: usize = 16;
+
+    /// Maximum length of a document URI in bytes
+    const MAX_DOCUMENT_URI_LEN: usize = 256;
+
+    /// Maximum memo length in bytes
+    const MAX_MEMO_LEN: usize = 128;
+
+    /// Maximum proposal description length in bytes
+    const MAX_DESCRIPTION_LEN: usize = 256;
+
+    /// Maximum number of open invoices per payer or payee
+    const MAX_OPEN_INVOICES: usize = 32;
+
+    /// Domain tag of signed mint vouchers
+    const MINT_VOUCHER_DOMAIN: &[u8] = b"SimpleToken:mint_voucher";
+
+    /// Domain tag of signed permits
+    const PERMIT_DOMAIN: &[u8] = b"SimpleToken:permit";
+
+    /// Domain tag of signed meta-transfer payloads
+    const META_TRANSFER_DOMAIN: &[u8] = b"SimpleToken:meta_transfer";
+
+    /// FNV-1a hash of a layout description
+    const fn layout_hash(layout: &str) -> u64 {
+        let bytes = layout.as_bytes();
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut i = 0;
+        while i < bytes.len() {
+            hash ^= bytes[i] as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+            i += 1;
+        }
+        hash
+    }
+
+    #[ink(storage)]
+    pub struct SimpleToken {
+        balances: Mapping<AccountId, TokenBalance>,
+        allowances: Mapping<(AccountId, AccountId), TokenBalance>, // (owner, spender) → allowance
+        owner: AccountId,
+        paused: bool, // transfers switch
+        blacklist: Mapping<AccountId, bool>,
+        layout_hash: Lazy<u64>, // absent on contracts deployed before the layout guard
+        donation: Lazy<DonationConfig>,
+        donation_opt_out: Mapping<AccountId, bool>,
+        total_donated: Lazy<TokenBalance>,
+        redemption: Lazy<RedemptionConfig>,
+        boost_collections: Lazy<Vec<(AccountId, u32)>>, // (PSP34 collection, factor bps)
+        partner_programs: Mapping<u32, u32>,              // program id → multiplier bps
+        partner_enrollment: Mapping<AccountId, u32>,      // account → program id
+        token_migration: Lazy<TokenMigration>,
+        snapshot_id: Lazy<u32>, // latest snapshot, 0 before the first one
+        balance_snapshots: Mapping<AccountId, SnapshotHistory>,
+        snapshot_airdrops: Mapping<u32, SnapshotAirdrop>,
+        snapshot_airdrop_count: Lazy<u32>,
+        snapshot_airdrop_claimed: Mapping<(u32, AccountId), ()>,
+        allowance_spent: Mapping<(AccountId, AccountId), TokenBalance>, // (owner, spender) → total spent
+        account_stats: Mapping<AccountId, AccountStats>,
+        total_supply: Lazy<TokenBalance>, // tracked from this release on
+        approval_notifiers: Mapping<AccountId, AccountId>, // owner → contract to notify
+        blacklist_info: Mapping<AccountId, BlacklistInfo>,
+        pause_info: Lazy<PauseInfo>,
+        documents: Mapping<[u8; 32], Document>,
+        document_names: Lazy<Vec<[u8; 32]>>,
+        sub_accounts: Mapping<AccountId, SubAccounts>,
+        sub_accounts_restricted: Mapping<AccountId, bool>,
+        dead_man_switch: Lazy<Option<DeadManSwitch>>,
+        inheritance: Mapping<AccountId, Inheritance>,
+        demurrage: Lazy<Option<Demurrage>>,
+        demurrage_checkpoints: Mapping<AccountId, Timestamp>, // last time decay was applied
+        spending_limits: Mapping<AccountId, SpendingLimit>,
+        pending_spending_limits: Mapping<AccountId, (SpendingLimit, Timestamp)>, // limit, effective at
+        spending_usage: Mapping<AccountId, (u64, TokenBalance)>, // (day, amount sent that day)
+        burn_allowances: Mapping<(AccountId, AccountId), TokenBalance>, // (owner, burner) → allowance
+        mint_references: Mapping<[u8; 32], ()>, // references already minted against
+        used_mint_vouchers: Mapping<u64, ()>,
+        attestations: Mapping<AccountId, Attestation>,
+        native_fee: Lazy<Option<NativeFee>>,
+        permissions: Mapping<(AccountId, MessageSelector), Timestamp>, // grantee, selector -> expiry
+        approval_limits: Mapping<AccountId, ApprovalLimit>,
+        pending_approval_limits: Mapping<AccountId, (ApprovalLimit, Timestamp)>, // limit, effective at
+        approval_usage: Mapping<AccountId, (u64, TokenBalance)>, // (day, allowance granted that day)
+        supply_observers: Lazy<Vec<AccountId>>,
+        balance_observers: Mapping<AccountId, AccountId>,
+        holds: Mapping<[u8; 32], Hold>,
+        held_balances: Mapping<AccountId, TokenBalance>,
+        hold_operators: Mapping<(AccountId, AccountId), ()>, // (account, operator)
+        invoices: Mapping<u32, Invoice>,
+        invoice_count: Lazy<u32>,
+        payer_invoices: Mapping<AccountId, Vec<u32>>, // open invoice ids
+        payee_invoices: Mapping<AccountId, Vec<u32>>, // open invoice ids
+        arbiters: Mapping<AccountId, ()>,
+        reversible_transfers: Mapping<u32, ReversibleTransfer>,
+        reversible_transfer_count: Lazy<u32>,
+        distribution_jobs: Mapping<u32, DistributionJob>,
+        distribution_job_count: Lazy<u32>,
+        distribution_entries: Mapping<(u32, u32), DistributionEntry>, // (job, index)
+        cosign_policies: Mapping<AccountId, CosignPolicy>,
+        cosigned_transfers: Mapping<u32, CosignedTransfer>,
+        cosigned_transfer_count: Lazy<u32>,
+        metadata: Lazy<TokenMetadata>,
+        max_supply: Lazy<TokenBalance>, // unset means uncapped
+        roles: Mapping<(RoleId, AccountId), ()>,
+        permit_nonces: Mapping<AccountId, u64>, // shared by every signed operation
+        fee_bps: Lazy<u16>,
+        treasury: Lazy<Option<AccountId>>,
+        fee_exempt: Mapping<AccountId, ()>,
+        burn_rate_bps: Lazy<u16>,
+        whitelist: Mapping<AccountId, ()>,
+        restricted_mode: Lazy<bool>,
+        blacklisted_accounts: Lazy<Vec<AccountId>>,
+        minting_paused: Lazy<bool>,
+        burning_paused: Lazy<bool>,
+        pause_deadline: Lazy<Option<Timestamp>>,
+        guardian: Lazy<Option<AccountId>>,
+        vesting: Mapping<AccountId, VestingSchedule>,
+        staking: Lazy<StakingPool>,
+        stakes: Mapping<AccountId, Stake>,
+        delegates: Mapping<AccountId, AccountId>,
+        vote_checkpoints: Mapping<AccountId, Vec<Checkpoint>>,
+        governance: Lazy<GovernanceConfig>,
+        proposals: Mapping<u32, Proposal>,
+        proposal_count: Lazy<u32>,
+        proposal_votes: Mapping<(u32, AccountId), ()>, // (proposal, voter) has voted
+        timelock_delay: Lazy<Timestamp>,
+        scheduled_actions: Mapping<u32, ScheduledAction>,
+        scheduled_action_count: Lazy<u32>,
+        merkle_airdrop: Lazy<Option<MerkleAirdrop>>,
+        merkle_airdrop_claimed: Mapping<(Hash, Hash), ()>, // (root, leaf) claimed
+        streams: Mapping<u32, Stream>,
+        stream_count: Lazy<u32>,
+        escrows: Mapping<u32, Escrow>,
+        escrow_count: Lazy<u32>,
+        upgrades_renounced: Lazy<bool>,
+        storage_version: Lazy<u32>, // absent (0) on contracts deployed as 0.1.0
+        flash_fee_bps: Lazy<u16>,
+        holders: Mapping<u32, AccountId>, // dense, 0..holder_count
+        holder_index: Mapping<AccountId, u32>, // position in `holders`
+        holder_count: Lazy<u32>,
+        transfer_guards: Lazy<TransferGuards>,
+        allowance_expiries: Mapping<(AccountId, AccountId), Timestamp>, // absent: no deadline
+        operators: Mapping<(AccountId, AccountId), ()>, // (owner, operator)
+        rate_limited_allowances: Mapping<(AccountId, AccountId), RateLimitedAllowance>,
+        trusted_forwarder: Lazy<Option<AccountId>>,
+        meta_sender: Lazy<Option<AccountId>>, // signer of the meta-transfer being executed
+        compliance_contract: Lazy<Option<AccountId>>,
+        kyc_registry: Lazy<Option<AccountId>>,
+        kyc_threshold: Lazy<TokenBalance>,
+        bridge_mint_caps: Mapping<AccountId, TokenBalance>,
+        bridge_outstanding: Mapping<AccountId, TokenBalance>, // minted minus burned, per bridge
+        cross_chain_routes: Mapping<u32, CrossChainRoute>, // by destination parachain id
+        cross_chain_transfer_count: Lazy<u64>,
+        transfer_hooks: Lazy<Vec<AccountId>>,
+        dividend_index: Lazy<u128>, // dividends per token, scaled by `DIVIDEND_MAGNITUDE`
+        dividend_accounts: Mapping<AccountId, DividendAccount>,
+        sale: Lazy<Option<Sale>>,
+        sale_id: Lazy<u32>,
+        sale_contributions: Mapping<(u32, AccountId), Balance>, // (sale id, contributor)
+        faucet: Lazy<Option<FaucetConfig>>, // absent: faucet disabled
+        faucet_claims: Mapping<AccountId, Timestamp>, // last claim
+        buyback_router: Lazy<Option<AccountId>>,
+        treasury_held: Lazy<TokenBalance>, // part of our own balance owned by the treasury
+        emission: Lazy<Option<EmissionSchedule>>,
+        emission_minted_until: Lazy<BlockNumber>, // emissions up to here are minted
+    }
+
+    /// Per-block emission halving every `halving_interval` blocks
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct EmissionSchedule {
+        /// Receives every minted tranche
+        pub distributor: AccountId,
+        /// Tokens emitted per block in the first epoch
+        pub initial_per_block: TokenBalance,
+        /// Blocks per epoch, the rate halves at each epoch boundary
+        pub halving_interval: BlockNumber,
+        /// First block of the first epoch
+        pub start_block: BlockNumber,
+    }
+
+    impl EmissionSchedule {
+        /// Total emitted from `start_block` up to `block`
+        fn emitted_until(&self, block: BlockNumber) -> TokenBalance {
+            let mut total: TokenBalance = 0;
+            let mut rate = self.initial_per_block;
+            let mut epoch_start = self.start_block;
+            // the rate reaches zero after at most 128 halvings
+            while rate > 0 && epoch_start < block {
+                let epoch_end = epoch_start.saturating_add(self.halving_interval);
+                let blocks = u128::from(epoch_end.min(block).saturating_sub(epoch_start));
+                total = total.saturating_add(rate.saturating_mul(blocks));
+                rate >>= 1;
+                epoch_start = epoch_end;
+            }
+            total
+        }
+    }
+
+    /// Testnet faucet settings
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct FaucetConfig {
+        /// Minted per claim
+        pub amount: TokenBalance,
+        /// Time an account must wait between claims
+        pub cooldown: Timestamp,
+    }
+
+    /// Token sale for native currency
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Sale {
+        /// Token units per native unit, scaled by `SALE_RATE_SCALE`
+        pub rate: u128,
+        pub start: Timestamp,
+        pub end: Timestamp,
+        /// Most native currency one account may contribute
+        pub account_cap: Balance,
+        /// Most native currency the sale accepts in total
+        pub hard_cap: Balance,
+        /// Non-zero: purchased tokens vest linearly over this long from `end`
+        pub vesting_duration: Timestamp,
+        /// Native currency contributed so far
+        pub raised: Balance,
+        pub finalized: bool,
+    }
+
+    /// An account's dividends accrued up to `index`
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct DividendAccount {
+        /// Dividend index when `accrued` was last updated
+        pub index: u128,
+        /// Unwithdrawn dividends
+        pub accrued: TokenBalance,
+    }
+
+    impl DividendAccount {
+        /// Dividends accrued on `balance` once the dividend index reaches `index`
+        fn accrued_at(&self, balance: TokenBalance, index: u128) -> TokenBalance {
+            let earned = mul_div(balance, index.saturating_sub(self.index), DIVIDEND_MAGNITUDE)
+                .unwrap_or(TokenBalance::MAX);
+            self.accrued.saturating_add(earned)
+        }
+    }
+
+    /// How tokens burned here are credited on a destination parachain: the
+    /// runtime call `remote_call` followed by the SCALE encoding of
+    /// `(transfer_id, beneficiary, amount)`, sent as an XCM `Transact`
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct CrossChainRoute {
+        /// Encoded call prefix on the destination, e.g. the counterpart
+        /// token's mint entry point
+        pub remote_call: Vec<u8>,
+        pub ref_time: u64,
+        pub proof_size: u64,
+    }
+
+    /// Standing pull right of up to `amount_per_period` per period, refilled
+    /// at the start of every period
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct RateLimitedAllowance {
+        pub amount_per_period: TokenBalance,
+        /// Period length in milliseconds
+        pub period: Timestamp,
+        /// Start of the period `spent` belongs to
+        pub period_start: Timestamp,
+        /// Amount drawn in the current period
+        pub spent: TokenBalance,
+    }
+
+    impl RateLimitedAllowance {
+        /// Move to the period containing `now`, refilling if it is a new one
+        fn roll(&mut self, now: Timestamp) {
+            let elapsed = now.saturating_sub(self.period_start);
+            if elapsed >= self.period {
+                let whole_periods = elapsed / self.period * self.period;
+                self.period_start = self.period_start.saturating_add(whole_periods);
+                self.spent = 0;
+            }
+        }
+
+        /// Amount still available in the current period
+        fn remaining(&self) -> TokenBalance {
+            self.amount_per_period.saturating_sub(self.spent)
+        }
+    }
+
+    /// Handling of degenerate transfers. By default zero-amount and
+    /// self-transfers succeed without touching storage, while transfers to
+    /// the zero address or to this contract are rejected.
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct TransferGuards {
+        /// Fail zero-amount transfers instead of skipping them
+        pub reject_zero_amount: bool,
+        /// Fail transfers to the sender instead of skipping them
+        pub reject_self_transfer: bool,
+        /// Accept transfers to the all-zero account
+        pub allow_zero_address: bool,
+        /// Accept transfers to this contract's own address
+        pub allow_to_contract: bool,
+    }
+
+    /// Tokens held in custody until the arbiter or sender releases them to
+    /// the beneficiary, or refunded to the sender
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Escrow {
+        pub sender: AccountId,
+        pub beneficiary: AccountId,
+        pub arbiter: AccountId,
+        pub amount: TokenBalance,
+        /// From then on the sender can take the funds back
+        pub refundable_at: Timestamp,
+    }
+
+    /// Deposit held in custody and streamed linearly to `recipient`
+    /// between `start` and `stop`
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Stream {
+        pub sender: AccountId,
+        pub recipient: AccountId,
+        pub deposit: TokenBalance,
+        pub start: Timestamp,
+        pub stop: Timestamp,
+        /// Amount the recipient already withdrew
+        pub withdrawn: TokenBalance,
+    }
+
+    impl Stream {
+        /// Amount streamed to the recipient by `now`, withdrawn or not
+        fn streamed_at(&self, now: Timestamp) -> TokenBalance {
+            if now <= self.start {
+                0
+            } else if now >= self.stop {
+                self.deposit
+            } else {
+                let elapsed = u128::from(now.saturating_sub(self.start));
+                let duration = u128::from(self.stop.saturating_sub(self.start));
+                mul_div(self.deposit, elapsed, duration).unwrap_or(self.deposit)
+            }
+        }
+    }
+
+    /// Airdrop of custody-held tokens to the (account, amount) leaves of a
+    /// merkle tree
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct MerkleAirdrop {
+        /// Root over leaves `blake2x256(SCALE(account, amount))`, pairs hashed
+        /// in sorted order
+        pub root: Hash,
+        /// Last time a claim is accepted, after which the rest can be swept
+        pub deadline: Timestamp,
+        /// Amount minted for the airdrop and not yet claimed
+        pub remaining: TokenBalance,
+    }
+
+    /// Timelocked admin action and when it may run
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct ScheduledAction {
+        pub action: AdminAction,
+        pub scheduled_by: AccountId,
+        /// Earliest time `execute_action` accepts it
+        pub eta: Timestamp,
+    }
+
+    /// Voting rules for governance proposals
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct GovernanceConfig {
+        /// Blocks a proposal stays open for votes, zero disables proposing
+        pub voting_period: BlockNumber,
+        /// Votes in favour a proposal needs to pass
+        pub quorum: TokenBalance,
+    }
+
+    /// Admin action run by a passed proposal or through the timelock
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum AdminAction {
+        Mint { to: AccountId, amount: TokenBalance },
+        SetPaused(bool),
+        SetBlacklist { account: AccountId, state: bool },
+        SetFeeBps(u16),
+        SetTreasury(Option<AccountId>),
+        SetTimelockDelay(Timestamp),
+        UpgradeCode(Hash),
+    }
+
+    impl AdminAction {
+        /// Selector of the admin message performing the same change
+        fn selector(&self) -> MessageSelector {
+            match self {
+                AdminAction::Mint { .. } => ink::selector_bytes!("mint"),
+                AdminAction::SetPaused(_) => ink::selector_bytes!("set_paused"),
+                AdminAction::SetBlacklist { .. } => ink::selector_bytes!("set_blacklist"),
+                AdminAction::SetFeeBps(_) => ink::selector_bytes!("set_fee_bps"),
+                AdminAction::SetTreasury(_) => ink::selector_bytes!("set_treasury"),
+                AdminAction::SetTimelockDelay(_) => ink::selector_bytes!("set_timelock_delay"),
+                AdminAction::UpgradeCode(_) => ink::selector_bytes!("upgrade_code"),
+            }
+        }
+    }
+
+    /// Governance proposal, voted on with delegated votes as of `start_block`
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Proposal {
+        pub proposer: AccountId,
+        /// Blake2x256 hash of the SCALE-encoded `AdminAction`
+        pub call_hash: Hash,
+        pub description: String,
+        pub start_block: BlockNumber,
+        /// Last block votes are accepted in
+        pub end_block: BlockNumber,
+        pub for_votes: TokenBalance,
+        pub against_votes: TokenBalance,
+        pub executed: bool,
+    }
+
+    /// Voting power of a delegate from `.0` (block number) on
+    pub type Checkpoint = (BlockNumber, TokenBalance);
+
+    /// Global staking state
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct StakingPool {
+        /// Reward units per staked unit per block, scaled by `REWARD_RATE_SCALE`
+        pub rate: u128,
+        /// Rewards accrued per staked unit since launch, scaled by
+        /// `REWARD_RATE_SCALE`, as of `last_block`
+        pub index: u128,
+        /// Block `index` was last brought up to date
+        pub last_block: BlockNumber,
+        /// Sum of all stakes
+        pub total_staked: TokenBalance,
+    }
+
+    impl StakingPool {
+        /// Reward index at `block`
+        fn index_at(&self, block: BlockNumber) -> u128 {
+            let blocks = u128::from(block.saturating_sub(self.last_block));
+            self.index.saturating_add(self.rate.saturating_mul(blocks))
+        }
+    }
+
+    /// An account's stake and its rewards accrued up to `index`
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Stake {
+        pub amount: TokenBalance,
+        /// Pool reward index when `accrued` was last updated
+        pub index: u128,
+        /// Unclaimed rewards
+        pub accrued: TokenBalance,
+    }
+
+    impl Stake {
+        /// Rewards accrued once the pool index reaches `index`
+        fn accrued_at(&self, index: u128) -> TokenBalance {
+            let earned = mul_div(self.amount, index.saturating_sub(self.index), REWARD_RATE_SCALE)
+                .unwrap_or(TokenBalance::MAX);
+            self.accrued.saturating_add(earned)
+        }
+    }
+
+    /// Tokens locked in custody for a beneficiary and released linearly
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct VestingSchedule {
+        /// Amount vested by the end of the schedule
+        pub total: TokenBalance,
+        /// Amount already claimed
+        pub released: TokenBalance,
+        /// Time vesting starts
+        pub start: Timestamp,
+        /// Time after `start` before which nothing can be claimed
+        pub cliff: Timestamp,
+        /// Time after `start` at which everything has vested
+        pub duration: Timestamp,
+        /// Whether an admin may revoke the unvested remainder
+        pub revocable: bool,
+        /// Revoked: `total` was cut to the amount vested at revocation
+        pub revoked: bool,
+    }
+
+    impl VestingSchedule {
+        /// Amount vested at `now`, including what was already claimed
+        fn vested_at(&self, now: Timestamp) -> TokenBalance {
+            let elapsed = now.saturating_sub(self.start);
+            if self.revoked {
+                self.total
+            } else if now < self.start || elapsed < self.cliff {
+                0
+            } else if elapsed >= self.duration {
+                self.total
+            } else {
+                // elapsed < duration, so the result is below total
+                mul_div(self.total, u128::from(elapsed), u128::from(self.duration))
+                    .unwrap_or(self.total)
+            }
+        }
+    }
+
+    /// Display metadata set at construction
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct TokenMetadata {
+        pub name: Option<String>,
+        pub symbol: Option<String>,
+        pub decimals: u8,
+    }
+
+    /// Outgoing transfers above `threshold` need `cosigner`'s approval
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct CosignPolicy {
+        pub cosigner: AccountId,
+        pub threshold: TokenBalance,
+    }
+
+    /// High-value transfer waiting for co-signature and execution
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct CosignedTransfer {
+        pub from: AccountId,
+        pub to: AccountId,
+        pub amount: TokenBalance,
+        pub approved: bool,
+    }
+
+    /// One payout of a distribution job, as (recipient, amount)
+    pub type DistributionEntry = (AccountId, TokenBalance);
+
+    /// Large payout executed in chunks from contract custody
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct DistributionJob {
+        /// Account that registered and funded the job
+        pub creator: AccountId,
+        /// Entries registered so far
+        pub entries: u32,
+        /// Entries processed so far
+        pub processed: u32,
+        /// Entries refunded to the creator because the recipient is blacklisted
+        pub skipped: u32,
+        /// Total amount registered
+        pub total: TokenBalance,
+        /// Total amount paid out
+        pub paid: TokenBalance,
+    }
+
+    /// Transfer held in contract custody until its chargeback window ends
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct ReversibleTransfer {
+        pub from: AccountId,
+        pub to: AccountId,
+        pub amount: TokenBalance,
+        /// End of the chargeback window, the recipient can claim from then on
+        pub claimable_at: Timestamp,
+    }
+
+    /// Lifecycle of an invoice
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum InvoiceStatus {
+        Open,
+        Paid,
+        Cancelled,
+    }
+
+    /// Payment request from a payee to a payer
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Invoice {
+        pub payee: AccountId,
+        pub payer: AccountId,
+        pub amount: TokenBalance,
+        pub memo: String,
+        /// Invoice can no longer be paid after this time
+        pub expiry: Timestamp,
+        pub status: InvoiceStatus,
+    }
+
+    /// Funds reserved in place for a pending payment
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Hold {
+        /// Account whose balance is reserved
+        pub account: AccountId,
+        /// Who placed the hold and alone can release or execute it
+        pub operator: AccountId,
+        /// Reserved amount
+        pub amount: TokenBalance,
+        /// When the hold was placed
+        pub created_at: Timestamp,
+    }
+
+    /// Native-currency protocol fee charged on transfer messages
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct NativeFee {
+        /// Exact native value a transfer message must carry
+        pub amount: Balance,
+        /// Receiver of the collected fees
+        pub treasury: AccountId,
+    }
+
+    /// Compliance attestation recorded for an account
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Attestation {
+        /// Issuer-defined verification level, higher is stronger
+        pub level: u8,
+        /// ISO 3166-1 numeric jurisdiction code
+        pub jurisdiction: u16,
+        /// Time after which the attestation no longer counts
+        pub expires_at: Timestamp,
+        /// Compliance officer who recorded it
+        pub attestor: AccountId,
+    }
+
+    /// Outbound limits an account imposes on itself
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct SpendingLimit {
+        /// Largest single outgoing transfer
+        pub max_per_transfer: TokenBalance,
+        /// Largest total sent per UTC day
+        pub max_per_day: TokenBalance,
+        /// Delay before a looser limit takes effect, in milliseconds
+        pub unlock_delay: Timestamp,
+    }
+
+    impl SpendingLimit {
+        /// Whether switching to `other` relaxes any part of this limit
+        fn is_loosened_by(&self, other: &SpendingLimit) -> bool {
+            other.max_per_transfer > self.max_per_transfer
+                || other.max_per_day > self.max_per_day
+                || other.unlock_delay < self.unlock_delay
+        }
+    }
+
+    /// Cap on new allowance an account can grant per day
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct ApprovalLimit {
+        /// Largest total allowance increase per UTC day
+        pub max_per_day: TokenBalance,
+        /// Delay before a looser limit or an opt-out takes effect, in milliseconds
+        pub unlock_delay: Timestamp,
+    }
+
+    impl ApprovalLimit {
+        /// Whether switching to `other` relaxes any part of this limit
+        fn is_loosened_by(&self, other: &ApprovalLimit) -> bool {
+            other.max_per_day > self.max_per_day || other.unlock_delay < self.unlock_delay
+        }
+    }
+
+    /// Balance decay settings
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Demurrage {
+        /// Share of a balance that decays per period, in basis points
+        pub rate_bps: u16,
+        /// Decay period in milliseconds
+        pub period: Timestamp,
+        /// Receiver of decayed tokens (exempt from decay), `None` burns them
+        pub sink: Option<AccountId>,
+    }
+
+    /// Estate recovery plan of an account
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Inheritance {
+        /// Account that may claim the balance
+        pub beneficiary: AccountId,
+        /// Inactivity after which a claim may start, in milliseconds
+        pub inactivity_period: Timestamp,
+        /// Last time the account sent, burned or touched this plan
+        pub last_active: Timestamp,
+        /// Start of a pending claim
+        pub claim_started: Option<Timestamp>,
+    }
+
+    /// Ownership failover to a backup account after owner inactivity
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct DeadManSwitch {
+        /// Account allowed to claim ownership
+        pub backup: AccountId,
+        /// Inactivity period after which the backup may claim, in milliseconds
+        pub timeout: Timestamp,
+        /// Time of the owner's last heartbeat
+        pub last_ping: Timestamp,
+    }
+
+    /// Named buckets earmarking parts of an account's balance, as (label, amount)
+    pub type SubAccounts = Vec<([u8; 32], TokenBalance)>;
+
+    /// First four bytes of a message selector
+    pub type MessageSelector = [u8; 4];
+
+    /// Identifier of an access-control role
+    pub type RoleId = u32;
+
+    /// Holders pass every admin check and manage all roles. The owner always
+    /// holds it implicitly.
+    pub const DEFAULT_ADMIN_ROLE: RoleId = 0;
+    /// Mint messages
+    pub const MINTER_ROLE: RoleId = 1;
+    /// Pause messages
+    pub const PAUSER_ROLE: RoleId = 2;
+    /// Blacklist messages and seizing blacklisted funds
+    pub const BLACKLISTER_ROLE: RoleId = 3;
+    /// Recording and revoking compliance attestations, whitelist messages, the
+    /// compliance contract and KYC registry settings
+    pub const COMPLIANCE_ROLE: RoleId = 4;
+    /// Bridge minting and burning within the bridge's mint cap
+    pub const BRIDGE_ROLE: RoleId = 5;
+
+    /// Role that unlocks an admin message, besides `DEFAULT_ADMIN_ROLE`
+    fn role_for(selector: MessageSelector) -> Option<RoleId> {
+        const MINT: [MessageSelector; 7] = [
+            ink::selector_bytes!("mint"),
+            ink::selector_bytes!("mint_with_reference"),
+            ink::selector_bytes!("PSP22Mintable::mint"),
+            ink::selector_bytes!("create_vesting"),
+            ink::selector_bytes!("start_merkle_airdrop"),
+            ink::selector_bytes!("airdrop"),
+            ink::selector_bytes!("mint_many"),
+        ];
+        const PAUSE: [MessageSelector; 6] = [
+            ink::selector_bytes!("set_paused"),
+            ink::selector_bytes!("set_paused_with_reason"),
+            ink::selector_bytes!("pause_transfers"),
+            ink::selector_bytes!("pause_until"),
+            ink::selector_bytes!("pause_minting"),
+            ink::selector_bytes!("pause_burning"),
+        ];
+        const BLACKLIST: [MessageSelector; 3] = [
+            ink::selector_bytes!("set_blacklist"),
+            ink::selector_bytes!("set_blacklist_with_reason"),
+            ink::selector_bytes!("seize"),
+        ];
+        const COMPLIANCE: [MessageSelector; 4] = [
+            ink::selector_bytes!("set_whitelist"),
+            ink::selector_bytes!("set_compliance_contract"),
+            ink::selector_bytes!("set_kyc_registry"),
+            ink::selector_bytes!("set_kyc_threshold"),
+        ];
+        if MINT.contains(&selector) {
+            Some(MINTER_ROLE)
+        } else if PAUSE.contains(&selector) {
+            Some(PAUSER_ROLE)
+        } else if BLACKLIST.contains(&selector) {
+            Some(BLACKLISTER_ROLE)
+        } else if COMPLIANCE.contains(&selector) {
+            Some(COMPLIANCE_ROLE)
+        } else {
+            None
+        }
+    }
+
+    /// Admin messages that must go through `schedule_action` while a
+    /// timelock delay is set
+    fn is_timelocked(selector: MessageSelector) -> bool {
+        const TIMELOCKED: [MessageSelector; 13] = [
+            ink::selector_bytes!("mint"),
+            ink::selector_bytes!("airdrop"),
+            ink::selector_bytes!("mint_many"),
+            ink::selector_bytes!("mint_with_reference"),
+            ink::selector_bytes!("PSP22Mintable::mint"),
+            ink::selector_bytes!("set_blacklist"),
+            ink::selector_bytes!("set_blacklist_with_reason"),
+            ink::selector_bytes!("set_paused"),
+            ink::selector_bytes!("set_paused_with_reason"),
+            ink::selector_bytes!("set_fee_bps"),
+            ink::selector_bytes!("set_treasury"),
+            ink::selector_bytes!("set_timelock_delay"),
+            ink::selector_bytes!("upgrade_code"),
+        ];
+        TIMELOCKED.contains(&selector)
+    }
+
+    /// Document attached to the token (ERC-1643 style)
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Document {
+        /// Where the document can be fetched
+        pub uri: String,
+        /// Hash of the document contents
+        pub content_hash: Hash,
+        /// Time of the last update
+        pub updated_at: Timestamp,
+    }
+
+    /// Independent pause switches
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum PauseSwitch {
+        Transfers,
+        Minting,
+        Burning,
+    }
+
+    /// Last change of the transfers pause switch
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct PauseInfo {
+        /// Pause state set by the change
+        pub paused: bool,
+        /// Account that made the change
+        pub by: AccountId,
+        /// Time of the change
+        pub at: Timestamp,
+        /// Issuer-defined reason code, 0 when none was given
+        pub reason: u32,
+    }
+
+    /// Why and by whom an account was blacklisted
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct BlacklistInfo {
+        /// Issuer-defined reason code, 0 when none was given
+        pub reason: u32,
+        /// Admin who blacklisted the account
+        pub admin: AccountId,
+        /// Time the account was blacklisted
+        pub since: Timestamp,
+    }
+
+    /// Breakdown of a transfer
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct FeeQuote {
+        /// Amount debited from the sender
+        pub amount: TokenBalance,
+        /// Share donated to the charity
+        pub donation: TokenBalance,
+        /// Charity receiving the donation
+        pub charity: Option<AccountId>,
+        /// Transfer fee routed to the treasury
+        pub fee: TokenBalance,
+        /// Treasury receiving the fee
+        pub treasury: Option<AccountId>,
+        /// Share burned under the burn-on-transfer rate
+        pub burned: TokenBalance,
+        /// Amount credited to the recipient
+        pub net: TokenBalance,
+    }
+
+    /// Result of `validate_batch`
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct BatchValidation {
+        /// Sum of all amounts
+        pub total: TokenBalance,
+        /// Index and error of every entry that would fail
+        pub failures: Vec<(u32, Error)>,
+        /// Estimated cost of the whole batch
+        pub weight_class: WeightClass,
+    }
+
+    /// Rough cost bucket of a batch, by number of entries
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum WeightClass {
+        /// Up to 50 entries
+        Light,
+        /// Up to 200 entries
+        Medium,
+        /// Up to 500 entries
+        Heavy,
+        /// More than 500 entries, likely to exceed block weight: split it
+        TooHeavy,
+    }
+
+    impl WeightClass {
+        fn of(entries: usize) -> Self {
+            match entries {
+                0..=50 => WeightClass::Light,
+                51..=200 => WeightClass::Medium,
+                201..=500 => WeightClass::Heavy,
+                _ => WeightClass::TooHeavy,
+            }
+        }
+    }
+
+    /// Lifetime transfer totals of an account
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct AccountStats {
+        /// Tokens sent
+        pub sent: TokenBalance,
+        /// Tokens received
+        pub received: TokenBalance,
+        /// Outgoing transfers
+        pub sends: u32,
+        /// Incoming transfers
+        pub receives: u32,
+    }
+
+    /// Balances recorded for an account as (snapshot id, balance), oldest first
+    type SnapshotHistory = Vec<(u32, TokenBalance)>;
+
+    /// Airdrop paying holders in proportion to their balance at a snapshot
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct SnapshotAirdrop {
+        /// Snapshot the entitlements are computed from
+        pub snapshot_id: u32,
+        /// Tokens paid per snapshot balance, in basis points
+        pub ratio_bps: u32,
+        /// Tokens claimed so far
+        pub total_claimed: TokenBalance,
+    }
+
+    /// Swap of a predecessor PSP22 token into this one
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct TokenMigration {
+        /// Predecessor token contract
+        pub old_token: AccountId,
+        /// New tokens minted per `ratio_denominator` old tokens
+        pub ratio_numerator: u128,
+        pub ratio_denominator: u128,
+        /// Last timestamp at which swaps are accepted
+        pub deadline: Timestamp,
+        /// Burn received old tokens instead of locking them in this contract
+        pub burn_old: bool,
+        /// Old tokens received so far
+        pub total_old: TokenBalance,
+        /// New tokens minted so far
+        pub total_new: TokenBalance,
+        /// Number of swaps performed
+        pub swaps: u32,
+    }
+
+    /// Burn-to-redeem settings and the native reserve backing them
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct RedemptionConfig {
+        /// Native units paid per token unit, scaled by `REDEMPTION_RATE_SCALE`.
+        /// Zero disables redemption.
+        pub rate: u128,
+        /// Redemptions are temporarily halted
+        pub paused: bool,
+        /// Native currency set aside for redemptions
+        pub reserve: Balance,
+    }
+
+    /// Charity donation settings
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct DonationConfig {
+        /// Receiver of donations, `None` disables them
+        pub charity: Option<AccountId>,
+        /// Share of each transfer donated, in basis points
+        pub bps: u16,
+    }
+
+    /// Errors returned by the contract messages
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        /// Caller is not the contract owner
+        NotOwner,
+        /// Account balance is too low for the operation
+        InsufficientBalance,
+        /// Spender allowance is too low for the operation
+        AllowanceTooLow,
+        /// Transfers are paused
+        Paused,
+        /// Sender is blacklisted
+        SenderBlacklisted,
+        /// Recipient is blacklisted
+        RecipientBlacklisted,
+        /// Batch input vectors have different lengths
+        LengthMismatch,
+        /// Storage is already at the version of this code
+        AlreadyMigrated,
+        /// Stored layout is not the one this code can migrate from
+        LayoutMismatch,
+        /// Basis points value above 10_000
+        InvalidBps,
+        /// Arithmetic overflow
+        Overflow,
+        /// Redemption rate is not set
+        RedemptionDisabled,
+        /// Redemptions are paused
+        RedemptionPaused,
+        /// Native reserve cannot cover the redemption
+        InsufficientReserve,
+        /// Native currency transfer failed
+        NativeTransferFailed,
+        /// Reward multiplier below `NO_BOOST_BPS`
+        InvalidBoostFactor,
+        /// Partner program does not exist
+        UnknownPartnerProgram,
+        /// Document does not exist
+        UnknownDocument,
+        /// Document URI is longer than allowed
+        UriTooLong,
+        /// Sub-account limit reached
+        TooManySubAccounts,
+        /// Sub-account balance is too low for the operation
+        InsufficientSubBalance,
+        /// Timeout shorter than allowed
+        InvalidTimeout,
+        /// No dead-man switch is configured
+        DeadManSwitchNotSet,
+        /// Caller is not the configured backup account
+        NotBackup,
+        /// Owner pinged within the timeout
+        OwnerStillActive,
+        /// Account has no beneficiary
+        NoBeneficiary,
+        /// Caller is not the account's beneficiary
+        NotBeneficiary,
+        /// Account was active within its inactivity period
+        AccountStillActive,
+        /// No inheritance claim is pending
+        NoPendingClaim,
+        /// Inheritance claim is still in its challenge period
+        ChallengePeriodActive,
+        /// Mint reference was already used
+        ReferenceAlreadyUsed,
+        /// Signature does not match the expected signer
+        InvalidSignature,
+        /// Voucher or signed payload has expired
+        Expired,
+        /// Mint voucher was already redeemed
+        VoucherUsed,
+        /// Caller is not a compliance officer
+        NotComplianceOfficer,
+        /// Transferred native value differs from the required protocol fee
+        NativeFeeMismatch,
+        /// Transfer is above the sender's per-transfer limit
+        ExceedsTransferLimit,
+        /// Transfer would exceed the sender's daily limit
+        ExceedsDailyLimit,
+        /// Approval would exceed the owner's daily approval limit
+        ExceedsApprovalLimit,
+        /// Invalid token migration settings
+        InvalidMigrationConfig,
+        /// Token migration is not configured
+        MigrationNotConfigured,
+        /// Token migration deadline has passed
+        MigrationClosed,
+        /// Call to the predecessor token failed or was rejected
+        OldTokenCallFailed,
+        /// Snapshot id does not exist
+        InvalidSnapshot,
+        /// Airdrop id does not exist
+        UnknownAirdrop,
+        /// Airdrop already claimed by this account
+        AlreadyClaimed,
+        /// Nothing to claim
+        NothingToClaim,
+        /// Boost collection limit reached
+        TooManyBoostCollections,
+        /// Supply observer limit reached
+        TooManySupplyObservers,
+        /// A hold with this id already exists
+        HoldExists,
+        /// No hold with this id
+        UnknownHold,
+        /// Caller may not place or settle this hold
+        NotHoldOperator,
+        /// Memo longer than `MAX_MEMO_LEN`
+        MemoTooLong,
+        /// No invoice with this id
+        UnknownInvoice,
+        /// Invoice was already paid or cancelled
+        InvoiceClosed,
+        /// Caller is neither payer nor payee of the invoice
+        NotInvoiceParty,
+        /// Open invoice limit reached for the payer or payee
+        TooManyInvoices,
+        /// No pending reversible transfer with this id
+        UnknownTransfer,
+        /// Caller may not settle this reversible transfer
+        NotTransferParty,
+        /// Malformed packed batch payload
+        InvalidPayload,
+        /// No distribution job with this id
+        UnknownDistribution,
+        /// Caller did not create this distribution job
+        NotDistributionCreator,
+        /// Transfer exceeds the sender's co-signing threshold
+        CosignRequired,
+        /// Caller is not the co-signer
+        NotCosigner,
+        /// Transfer has not been co-signed yet
+        NotCosigned,
+        /// Mint would take the total supply above `max_supply`
+        MaxSupplyExceeded,
+        /// Recipient contract rejected the transfer or cannot receive tokens
+        TransferRejected,
+        /// Restricted mode is on and the sender is not whitelisted
+        SenderNotWhitelisted,
+        /// Restricted mode is on and the recipient is not whitelisted
+        RecipientNotWhitelisted,
+        /// Account is not blacklisted
+        NotBlacklisted,
+        /// Minting is paused
+        MintingPaused,
+        /// Burning is paused
+        BurningPaused,
+        /// Beneficiary already has a vesting schedule
+        VestingExists,
+        /// No vesting schedule for this beneficiary
+        UnknownVesting,
+        /// Zero duration or a cliff past the end of the schedule
+        InvalidVestingSchedule,
+        /// Vesting schedule is not revocable or was already revoked
+        NotRevocable,
+        /// Unstaking more than is staked
+        InsufficientStake,
+        /// Past votes are only known for blocks before the current one
+        FutureLookup,
+        /// Governance voting period is not configured
+        GovernanceDisabled,
+        /// Proposal description exceeds `MAX_DESCRIPTION_LEN`
+        DescriptionTooLong,
+        /// No proposal with this id
+        UnknownProposal,
+        /// Proposal is not open for votes
+        VotingClosed,
+        /// Voting on the proposal has not ended yet
+        VotingActive,
+        /// Caller already voted on this proposal
+        AlreadyVoted,
+        /// Proposal did not reach quorum or a majority in favour
+        ProposalRejected,
+        /// Proposal was already executed
+        ProposalExecuted,
+        /// Action does not match the proposal's call hash
+        CallHashMismatch,
+        /// Message is timelocked, use `schedule_action`
+        Timelocked,
+        /// No scheduled action with this id
+        UnknownAction,
+        /// Scheduled action's delay has not passed yet
+        TimelockPending,
+        /// A merkle airdrop still holds unclaimed tokens
+        AirdropActive,
+        /// No merkle airdrop is open for claims
+        AirdropNotActive,
+        /// Allocation was already claimed
+        AirdropClaimed,
+        /// Merkle proof does not lead to the airdrop root
+        InvalidProof,
+        /// Stream must stop after it starts
+        InvalidStream,
+        /// No stream with this id
+        UnknownStream,
+        /// Caller is not the stream's sender or recipient
+        NotStreamParty,
+        /// No escrow with this id
+        UnknownEscrow,
+        /// Caller may not settle this escrow
+        NotEscrowParty,
+        /// Escrow cannot be refunded before its timeout
+        EscrowActive,
+        /// Code upgrades were permanently disabled
+        UpgradesRenounced,
+        /// The runtime refused the new code hash
+        UpgradeFailed,
+        /// Flash loan receiver failed or rejected the callback
+        FlashLoanRejected,
+        /// This token's own balance cannot be rescued
+        CannotRescueSelf,
+        /// The rescued token refused the transfer
+        RescueFailed,
+        /// Zero-amount transfers are rejected
+        ZeroAmount,
+        /// Transfers to the sender are rejected
+        SelfTransfer,
+        /// Transfers to the zero address are rejected
+        ZeroRecipient,
+        /// Transfers to this contract are rejected
+        RecipientIsContract,
+        /// The allowance's deadline has passed
+        AllowanceExpired,
+        /// Period length must be non-zero
+        InvalidPeriod,
+        /// Caller is not the trusted forwarder
+        NotTrustedForwarder,
+        /// The compliance contract refused the transfer
+        ComplianceRejected,
+        /// Sender and recipient must be verified in the KYC registry
+        KycRequired,
+        /// Caller lacks the bridge role
+        NotBridge,
+        /// Mint would take the bridge past its cap
+        BridgeCapExceeded,
+        /// No cross-chain route to this parachain
+        UnknownRoute,
+        /// The runtime refused to send the XCM message
+        XcmSendFailed,
+        /// Registering the hook would exceed `MAX_TRANSFER_HOOKS`
+        TooManyTransferHooks,
+        /// A transfer hook failed or rejected the balance change
+        HookRejected,
+        /// No tokens outside custody to distribute dividends over
+        NoDividendRecipients,
+        /// A sale is already accepting contributions
+        SaleActive,
+        /// No sale is accepting contributions
+        SaleNotActive,
+        /// Sale must end after it starts
+        InvalidSaleWindow,
+        /// Contribution exceeds the account cap or the hard cap
+        SaleCapExceeded,
+        /// The faucet is switched off
+        FaucetDisabled,
+        /// The caller claimed from the faucet too recently
+        FaucetCooldown,
+        /// No DEX router is configured for buybacks
+        NoBuybackRouter,
+        /// The router call failed
+        BuybackFailed,
+        /// The swap returned fewer tokens than the minimum
+        BuybackSlippage,
+        /// The contract-managed treasury holds less than requested
+        InsufficientTreasury,
+        /// An emission schedule needs a non-zero halving interval
+        InvalidEmissionSchedule,
+        /// No emission schedule is configured
+        NoEmissionSchedule,
+    }
+
+    /// Result type of the contract messages
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl From<Error> for PSP22Error {
+        fn from(error: Error) -> Self {
+            match error {
+                Error::InsufficientBalance => PSP22Error::InsufficientBalance,
+                Error::AllowanceTooLow => PSP22Error::InsufficientAllowance,
+                Error::ZeroRecipient => PSP22Error::ZeroRecipientAddress,
+                Error::TransferRejected => {
+                    PSP22Error::SafeTransferCheckFailed(String::from("TransferRejected"))
+                }
+                other => PSP22Error::Custom(ink::prelude::format!("{:?}", other)),
+            }
+        }
+    }
+
+    // Events
+    #[ink(event)]
+    pub struct Mint {
+        #[ink(topic)]
+        to: AccountId,
+        amount: TokenBalance,
+        #[ink(topic)]
+        reference: Option<[u8; 32]>,
+    }
+
+    #[ink(event)]
+    pub struct Burn {
+        #[ink(topic)]
+        from: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct GuardianChanged {
+        #[ink(topic)]
+        guardian: Option<AccountId>,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct PauseSwitched {
+        switch: PauseSwitch,
+        paused: bool,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        by: AccountId,
+        reason: u32,
+    }
+
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        by: AccountId,
+        reason: u32,
+    }
+
+    #[ink(event)]
+    pub struct BlacklistUpdated {
+        #[ink(topic)]
+        account: AccountId,
+        blacklisted: bool,
+        reason: u32,
+        #[ink(topic)]
+        admin: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Seized {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: TokenBalance,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct WhitelistUpdated {
+        #[ink(topic)]
+        account: AccountId,
+        whitelisted: bool,
+        #[ink(topic)]
+        admin: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RestrictedModeChanged {
+        enabled: bool,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct SupplyIncreased {
+        by: TokenBalance,
+        new_total: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct SupplyDecreased {
+        by: TokenBalance,
+        new_total: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        spender: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct Redeemed {
+        #[ink(topic)]
+        account: AccountId,
+        amount: TokenBalance,
+        native_amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct BoostCollectionUpdated {
+        #[ink(topic)]
+        collection: AccountId,
+        factor_bps: Option<u32>,
+    }
+
+    #[ink(event)]
+    pub struct PartnerProgramUpdated {
+        #[ink(topic)]
+        program_id: u32,
+        multiplier_bps: Option<u32>,
+    }
+
+    #[ink(event)]
+    pub struct PartnerEnrolled {
+        #[ink(topic)]
+        account: AccountId,
+        program_id: Option<u32>,
+    }
+
+    #[ink(event)]
+    pub struct TokensMigrated {
+        #[ink(topic)]
+        account: AccountId,
+        old_amount: TokenBalance,
+        new_amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct Snapshot {
+        id: u32,
+    }
+
+    #[ink(event)]
+    pub struct SnapshotAirdropCreated {
+        #[ink(topic)]
+        airdrop_id: u32,
+        snapshot_id: u32,
+        ratio_bps: u32,
+    }
+
+    #[ink(event)]
+    pub struct SnapshotAirdropClaimed {
+        #[ink(topic)]
+        airdrop_id: u32,
+        #[ink(topic)]
+        account: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct AllowanceSpent {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        spender: AccountId,
+        amount: TokenBalance,
+        total_spent: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct DocumentUpdated {
+        #[ink(topic)]
+        name: [u8; 32],
+        uri: String,
+        content_hash: Hash,
+    }
+
+    #[ink(event)]
+    pub struct DocumentRemoved {
+        #[ink(topic)]
+        name: [u8; 32],
+    }
+
+    #[ink(event)]
+    pub struct SubBalanceChanged {
+        #[ink(topic)]
+        account: AccountId,
+        label: [u8; 32],
+        balance: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous_owner: AccountId,
+        #[ink(topic)]
+        new_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct BeneficiarySet {
+        #[ink(topic)]
+        account: AccountId,
+        beneficiary: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct InheritanceClaimStarted {
+        #[ink(topic)]
+        account: AccountId,
+        #[ink(topic)]
+        beneficiary: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct InheritanceClaimCancelled {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct InheritanceClaimed {
+        #[ink(topic)]
+        account: AccountId,
+        #[ink(topic)]
+        beneficiary: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct SpendingLimitChanged {
+        #[ink(topic)]
+        account: AccountId,
+        limit: Option<SpendingLimit>,
+        effective_at: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct ApprovalLimitChanged {
+        #[ink(topic)]
+        account: AccountId,
+        limit: Option<ApprovalLimit>,
+        effective_at: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct BurnApproval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        burner: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct RoleGranted {
+        #[ink(topic)]
+        role: RoleId,
+        #[ink(topic)]
+        account: AccountId,
+        sender: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RoleRevoked {
+        #[ink(topic)]
+        role: RoleId,
+        #[ink(topic)]
+        account: AccountId,
+        sender: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct AttestationUpdated {
+        #[ink(topic)]
+        account: AccountId,
+        attestation: Option<Attestation>,
+    }
+
+    #[ink(event)]
+    pub struct PermissionGranted {
+        #[ink(topic)]
+        account: AccountId,
+        selector: MessageSelector,
+        expires_at: Option<Timestamp>,
+    }
+
+    #[ink(event)]
+    pub struct PermissionRevoked {
+        #[ink(topic)]
+        account: AccountId,
+        selector: MessageSelector,
+    }
+
+    #[ink(event)]
+    pub struct SupplyObserverUpdated {
+        #[ink(topic)]
+        observer: AccountId,
+        enabled: bool,
+    }
+
+    #[ink(event)]
+    pub struct HoldPlaced {
+        #[ink(topic)]
+        hold_id: [u8; 32],
+        #[ink(topic)]
+        account: AccountId,
+        operator: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct HoldReleased {
+        #[ink(topic)]
+        hold_id: [u8; 32],
+    }
+
+    #[ink(event)]
+    pub struct HoldExecuted {
+        #[ink(topic)]
+        hold_id: [u8; 32],
+        #[ink(topic)]
+        to: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct InvoiceCreated {
+        #[ink(topic)]
+        invoice_id: u32,
+        #[ink(topic)]
+        payee: AccountId,
+        #[ink(topic)]
+        payer: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct InvoiceClosed {
+        #[ink(topic)]
+        invoice_id: u32,
+        status: InvoiceStatus,
+    }
+
+    #[ink(event)]
+    pub struct ArbiterUpdated {
+        #[ink(topic)]
+        account: AccountId,
+        enabled: bool,
+    }
+
+    #[ink(event)]
+    pub struct ReversibleTransferCreated {
+        #[ink(topic)]
+        transfer_id: u32,
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: TokenBalance,
+        claimable_at: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct ReversibleTransferSettled {
+        #[ink(topic)]
+        transfer_id: u32,
+        reversed: bool,
+    }
+
+    #[ink(event)]
+    pub struct DistributionProgress {
+        #[ink(topic)]
+        job_id: u32,
+        entries: u32,
+        processed: u32,
+        paid: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct CosignPolicyChanged {
+        #[ink(topic)]
+        account: AccountId,
+        policy: Option<CosignPolicy>,
+    }
+
+    #[ink(event)]
+    pub struct CosignedTransferRequested {
+        #[ink(topic)]
+        transfer_id: u32,
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct CosignedTransferApproved {
+        #[ink(topic)]
+        transfer_id: u32,
+    }
+
+    #[ink(event)]
+    pub struct CosignedTransferClosed {
+        #[ink(topic)]
+        transfer_id: u32,
+        executed: bool,
+    }
+
+    #[ink(event)]
+    pub struct FeeCharged {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        treasury: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct VestingCreated {
+        #[ink(topic)]
+        beneficiary: AccountId,
+        total: TokenBalance,
+        start: Timestamp,
+        cliff: Timestamp,
+        duration: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct VestingRevoked {
+        #[ink(topic)]
+        beneficiary: AccountId,
+        unvested: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct VestingClaimed {
+        #[ink(topic)]
+        beneficiary: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct Staked {
+        #[ink(topic)]
+        account: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct Unstaked {
+        #[ink(topic)]
+        account: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct RewardsClaimed {
+        #[ink(topic)]
+        account: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct DelegateChanged {
+        #[ink(topic)]
+        delegator: AccountId,
+        #[ink(topic)]
+        from_delegate: Option<AccountId>,
+        #[ink(topic)]
+        to_delegate: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct DelegateVotesChanged {
+        #[ink(topic)]
+        delegate: AccountId,
+        previous_votes: TokenBalance,
+        new_votes: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct ProposalCreated {
+        #[ink(topic)]
+        proposal_id: u32,
+        #[ink(topic)]
+        proposer: AccountId,
+        call_hash: Hash,
+        end_block: BlockNumber,
+    }
+
+    #[ink(event)]
+    pub struct VoteCast {
+        #[ink(topic)]
+        proposal_id: u32,
+        #[ink(topic)]
+        voter: AccountId,
+        support: bool,
+        weight: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct ProposalExecuted {
+        #[ink(topic)]
+        proposal_id: u32,
+    }
+
+    #[ink(event)]
+    pub struct ActionScheduled {
+        #[ink(topic)]
+        action_id: u32,
+        action: AdminAction,
+        #[ink(topic)]
+        scheduled_by: AccountId,
+        eta: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct ActionExecuted {
+        #[ink(topic)]
+        action_id: u32,
+    }
+
+    #[ink(event)]
+    pub struct ActionCancelled {
+        #[ink(topic)]
+        action_id: u32,
+    }
+
+    #[ink(event)]
+    pub struct MerkleAirdropStarted {
+        root: Hash,
+        total: TokenBalance,
+        deadline: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct MerkleAirdropClaimed {
+        #[ink(topic)]
+        account: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct MerkleAirdropSwept {
+        #[ink(topic)]
+        to: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct Airdropped {
+        count: u32,
+        total: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct StreamCreated {
+        #[ink(topic)]
+        stream_id: u32,
+        #[ink(topic)]
+        sender: AccountId,
+        #[ink(topic)]
+        recipient: AccountId,
+        deposit: TokenBalance,
+        start: Timestamp,
+        stop: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct StreamWithdrawn {
+        #[ink(topic)]
+        stream_id: u32,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct StreamCancelled {
+        #[ink(topic)]
+        stream_id: u32,
+        recipient_amount: TokenBalance,
+        sender_amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct EscrowCreated {
+        #[ink(topic)]
+        escrow_id: u32,
+        #[ink(topic)]
+        sender: AccountId,
+        #[ink(topic)]
+        beneficiary: AccountId,
+        arbiter: AccountId,
+        amount: TokenBalance,
+        refundable_at: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct EscrowSettled {
+        #[ink(topic)]
+        escrow_id: u32,
+        /// `true` if paid to the beneficiary, `false` if refunded
+        released: bool,
+    }
+
+    #[ink(event)]
+    pub struct CodeUpgraded {
+        #[ink(topic)]
+        code_hash: Hash,
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct UpgradesRenounced {
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct FlashLoan {
+        #[ink(topic)]
+        receiver: AccountId,
+        #[ink(topic)]
+        initiator: AccountId,
+        amount: TokenBalance,
+        fee: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct Rescued {
+        /// Token contract, `None` for native currency
+        #[ink(topic)]
+        token: Option<AccountId>,
+        #[ink(topic)]
+        to: AccountId,
+        amount: u128,
+    }
+
+    #[ink(event)]
+    pub struct TransferWithMemo {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: TokenBalance,
+        memo: Vec<u8>,
+    }
+
+    #[ink(event)]
+    pub struct OperatorUpdated {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        operator: AccountId,
+        approved: bool,
+    }
+
+    #[ink(event)]
+    pub struct RateLimitedApproval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        spender: AccountId,
+        amount_per_period: TokenBalance,
+        period: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct MetaTransferExecuted {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: TokenBalance,
+        nonce: u64,
+        relayer: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct NonceUsed {
+        #[ink(topic)]
+        account: AccountId,
+        nonce: u64,
+    }
+
+    #[ink(event)]
+    pub struct ComplianceContractChanged {
+        contract: Option<AccountId>,
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct BridgeMinted {
+        #[ink(topic)]
+        bridge: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct BridgeBurned {
+        #[ink(topic)]
+        bridge: AccountId,
+        #[ink(topic)]
+        from: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct CrossChainTransfer {
+        #[ink(topic)]
+        transfer_id: u64,
+        #[ink(topic)]
+        from: AccountId,
+        dest_parachain: u32,
+        beneficiary: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct TransferHookUpdated {
+        #[ink(topic)]
+        hook: AccountId,
+        enabled: bool,
+    }
+
+    #[ink(event)]
+    pub struct DividendsDistributed {
+        #[ink(topic)]
+        from: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct DividendWithdrawn {
+        #[ink(topic)]
+        account: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct SaleConfigured {
+        #[ink(topic)]
+        sale_id: u32,
+        rate: u128,
+        start: Timestamp,
+        end: Timestamp,
+        hard_cap: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Contributed {
+        #[ink(topic)]
+        sale_id: u32,
+        #[ink(topic)]
+        account: AccountId,
+        native_amount: Balance,
+        tokens: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct SaleFinalized {
+        #[ink(topic)]
+        sale_id: u32,
+        raised: Balance,
+    }
+
+    #[ink(event)]
+    pub struct FaucetClaimed {
+        #[ink(topic)]
+        account: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct Buyback {
+        #[ink(topic)]
+        router: AccountId,
+        /// Native currency spent
+        spent: Balance,
+        /// Tokens bought and burned
+        burned: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct TreasuryDeposit {
+        #[ink(topic)]
+        from: AccountId,
+        amount: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct TreasuryTransfer {
+        #[ink(topic)]
+        to: AccountId,
+        amount: TokenBalance,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct EmissionMinted {
+        #[ink(topic)]
+        distributor: AccountId,
+        amount: TokenBalance,
+        /// Emissions up to this block are now minted
+        until_block: BlockNumber,
+    }
+
+    #[ink(event)]
+    pub struct Migrated {
+        from_layout: u64,
+        to_layout: u64,
+        from_version: u32,
+        to_version: u32,
+    }
+
+    impl Default for SimpleToken {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl SimpleToken {
+        /// Constructor
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            let caller = Self::env().caller();
+            let mut layout_hash = Lazy::new();
+            layout_hash.set(&STORAGE_LAYOUT_HASH);
+            let mut storage_version = Lazy::new();
+            storage_version.set(&STORAGE_VERSION);
+            Self {
+                balances: Mapping::default(),
+                allowances: Mapping::default(),
+                owner: caller,
+                paused: false,
+                blacklist: Mapping::default(),
+                layout_hash,
+                donation: Lazy::new(),
+                donation_opt_out: Mapping::default(),
+                total_donated: Lazy::new(),
+                redemption: Lazy::new(),
+                boost_collections: Lazy::new(),
+                partner_programs: Mapping::default(),
+                partner_enrollment: Mapping::default(),
+                token_migration: Lazy::new(),
+                snapshot_id: Lazy::new(),
+                balance_snapshots: Mapping::default(),
+                snapshot_airdrops: Mapping::default(),
+                snapshot_airdrop_count: Lazy::new(),
+                snapshot_airdrop_claimed: Mapping::default(),
+                allowance_spent: Mapping::default(),
+                account_stats: Mapping::default(),
+                total_supply: Lazy::new(),
+                approval_notifiers: Mapping::default(),
+                blacklist_info: Mapping::default(),
+                pause_info: Lazy::new(),
+                documents: Mapping::default(),
+                document_names: Lazy::new(),
+                sub_accounts: Mapping::default(),
+                sub_accounts_restricted: Mapping::default(),
+                dead_man_switch: Lazy::new(),
+                inheritance: Mapping::default(),
+                demurrage: Lazy::new(),
+                demurrage_checkpoints: Mapping::default(),
+                spending_limits: Mapping::default(),
+                pending_spending_limits: Mapping::default(),
+                spending_usage: Mapping::default(),
+                burn_allowances: Mapping::default(),
+                mint_references: Mapping::default(),
+                used_mint_vouchers: Mapping::default(),
+                attestations: Mapping::default(),
+                native_fee: Lazy::new(),
+                permissions: Mapping::default(),
+                approval_limits: Mapping::default(),
+                pending_approval_limits: Mapping::default(),
+                approval_usage: Mapping::default(),
+                supply_observers: Lazy::new(),
+                balance_observers: Mapping::default(),
+                holds: Mapping::default(),
+                held_balances: Mapping::default(),
+                hold_operators: Mapping::default(),
+                invoices: Mapping::default(),
+                invoice_count: Lazy::new(),
+                payer_invoices: Mapping::default(),
+                payee_invoices: Mapping::default(),
+                arbiters: Mapping::default(),
+                reversible_transfers: Mapping::default(),
+                reversible_transfer_count: Lazy::new(),
+                distribution_jobs: Mapping::default(),
+                distribution_job_count: Lazy::new(),
+                distribution_entries: Mapping::default(),
+                cosign_policies: Mapping::default(),
+                cosigned_transfers: Mapping::default(),
+                cosigned_transfer_count: Lazy::new(),
+                metadata: Lazy::new(),
+                max_supply: Lazy::new(),
+                roles: Mapping::default(),
+                permit_nonces: Mapping::default(),
+                fee_bps: Lazy::new(),
+                treasury: Lazy::new(),
+                fee_exempt: Mapping::default(),
+                burn_rate_bps: Lazy::new(),
+                whitelist: Mapping::default(),
+                restricted_mode: Lazy::new(),
+                blacklisted_accounts: Lazy::new(),
+                minting_paused: Lazy::new(),
+                burning_paused: Lazy::new(),
+                pause_deadline: Lazy::new(),
+                guardian: Lazy::new(),
+                vesting: Mapping::default(),
+                staking: Lazy::new(),
+                stakes: Mapping::default(),
+                delegates: Mapping::default(),
+                vote_checkpoints: Mapping::default(),
+                governance: Lazy::new(),
+                proposals: Mapping::default(),
+                proposal_count: Lazy::new(),
+                proposal_votes: Mapping::default(),
+                timelock_delay: Lazy::new(),
+                scheduled_actions: Mapping::default(),
+                scheduled_action_count: Lazy::new(),
+                merkle_airdrop: Lazy::new(),
+                merkle_airdrop_claimed: Mapping::default(),
+                streams: Mapping::default(),
+                stream_count: Lazy::new(),
+                escrows: Mapping::default(),
+                escrow_count: Lazy::new(),
+                upgrades_renounced: Lazy::new(),
+                storage_version,
+                flash_fee_bps: Lazy::new(),
+                holders: Mapping::default(),
+                holder_index: Mapping::default(),
+                holder_count: Lazy::new(),
+                transfer_guards: Lazy::new(),
+                allowance_expiries: Mapping::default(),
+                operators: Mapping::default(),
+                rate_limited_allowances: Mapping::default(),
+                trusted_forwarder: Lazy::new(),
+                meta_sender: Lazy::new(),
+                compliance_contract: Lazy::new(),
+                kyc_registry: Lazy::new(),
+                kyc_threshold: Lazy::new(),
+                bridge_mint_caps: Mapping::default(),
+                bridge_outstanding: Mapping::default(),
+                cross_chain_routes: Mapping::default(),
+                cross_chain_transfer_count: Lazy::new(),
+                transfer_hooks: Lazy::new(),
+                dividend_index: Lazy::new(),
+                dividend_accounts: Mapping::default(),
+                sale: Lazy::new(),
+                sale_id: Lazy::new(),
+                sale_contributions: Mapping::default(),
+                faucet: Lazy::new(),
+                faucet_claims: Mapping::default(),
+                buyback_router: Lazy::new(),
+                treasury_held: Lazy::new(),
+                emission: Lazy::new(),
+                emission_minted_until: Lazy::new(),
+            }
+        }
+
+        /// Constructor with token name, symbol and display decimals
+        #[ink(constructor)]
+        pub fn new_with_metadata(
+            name: Option<String>,
+            symbol: Option<String>,
+            decimals: u8,
+        ) -> Self {
+            let mut token = Self::new();
+            token.metadata.set(&TokenMetadata {
+                name,
+                symbol,
+                decimals,
+            });
+            token
+        }
+
+        /// Constructor with metadata that also mints `initial_supply` to
+        /// `initial_holder`, so no separate mint transaction is needed
+        #[ink(constructor)]
+        pub fn new_with_config(
+            initial_supply: TokenBalance,
+            name: String,
+            symbol: String,
+            decimals: u8,
+            initial_holder: AccountId,
+        ) -> Self {
+            let mut token = Self::new_with_metadata(Some(name), Some(symbol), decimals);
+            if initial_supply > 0 {
+                // uncapped and starting from zero, so this mint cannot fail
+                let _ = token.mint_internal(initial_holder, initial_supply, None);
+            }
+            token
+        }
+
+        /// Like `new_with_config`, but no mint can ever take the total supply
+        /// above `max_supply`
+        #[ink(constructor)]
+        pub fn new_capped(
+            initial_supply: TokenBalance,
+            name: String,
+            symbol: String,
+            decimals: u8,
+            initial_holder: AccountId,
+            max_supply: TokenBalance,
+        ) -> Result<Self> {
+            let mut token = Self::new_with_metadata(Some(name), Some(symbol), decimals);
+            token.max_supply.set(&max_supply);
+            if initial_supply > 0 {
+                token.mint_internal(initial_holder, initial_supply, None)?;
+            }
+            Ok(token)
+        }
+
+        /// Supply cap set at construction, if any
+        #[ink(message)]
+        pub fn max_supply(&self) -> Option<TokenBalance> {
+            self.max_supply.get()
+        }
+
+        /// Internal check for pause/blacklist, the sender's own limits and its
+        /// co-signing threshold
+        fn can_transfer(
+            &self,
+            from: &AccountId,
+            to: &AccountId,
+            amount: TokenBalance,
+        ) -> Result<()> {
+            self.check_restrictions(from, to, amount)?;
+            self.check_cosign_threshold(from, amount)
+        }
+
+        /// `can_transfer` without the co-signing check, for co-signed transfers
+        fn check_restrictions(
+            &self,
+            from: &AccountId,
+            to: &AccountId,
+            amount: TokenBalance,
+        ) -> Result<()> {
+            self.check_sender(from, amount)?;
+            self.check_leg(from, to, amount)
+        }
+
+        /// Sender-side checks against the total `amount` leaving `from`:
+        /// pause, sender blacklist and whitelist, and daily spending limit
+        fn check_sender(&self, from: &AccountId, amount: TokenBalance) -> Result<()> {
+            if self.is_paused() {
+                return Err(Error::Paused);
+            }
+            if self.blacklist.get(from).unwrap_or(false) {
+                return Err(Error::SenderBlacklisted);
+            }
+            if self.restricted_mode.get_or_default() && !self.whitelist.contains(from) {
+                return Err(Error::SenderNotWhitelisted);
+            }
+            if let Some(limit) = self.spending_limit_of(*from) {
+                if self.spent_today(*from).saturating_add(amount) > limit.max_per_day {
+                    return Err(Error::ExceedsDailyLimit);
+                }
+            }
+            Ok(())
+        }
+
+        /// Per-transfer checks: recipient blacklist and whitelist, and the
+        /// sender's per-transfer limit
+        fn check_leg(&self, from: &AccountId, to: &AccountId, amount: TokenBalance) -> Result<()> {
+            if self.blacklist.get(to).unwrap_or(false) {
+                return Err(Error::RecipientBlacklisted);
+            }
+            if self.restricted_mode.get_or_default() && !self.whitelist.contains(to) {
+                return Err(Error::RecipientNotWhitelisted);
+            }
+            if let Some(limit) = self.spending_limit_of(*from) {
+                if amount > limit.max_per_transfer {
+                    return Err(Error::ExceedsTransferLimit);
+                }
+            }
+            if let Some(compliance) = self.compliance_contract() {
+                let allowed = build_call::<Environment>()
+                    .call(compliance)
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(CHECK_TRANSFER))
+                            .push_arg(from)
+                            .push_arg(to)
+                            .push_arg(amount),
+                    )
+                    .returns::<bool>()
+                    .try_invoke();
+                // a compliance contract that traps or is missing blocks the transfer
+                if !matches!(allowed, Ok(Ok(true))) {
+                    return Err(Error::ComplianceRejected);
+                }
+            }
+            if let Some(registry) = self.kyc_registry() {
+                if amount > self.kyc_threshold()
+                    && !(self.is_kyc_verified(registry, from) && self.is_kyc_verified(registry, to))
+                {
+                    return Err(Error::KycRequired);
+                }
+            }
+            Ok(())
+        }
+
+        /// Ask the KYC registry about `account`; an unreachable registry
+        /// counts as not verified
+        fn is_kyc_verified(&self, registry: AccountId, account: &AccountId) -> bool {
+            let verified = build_call::<Environment>()
+                .call(registry)
+                .exec_input(ExecutionInput::new(Selector::new(IS_VERIFIED)).push_arg(account))
+                .returns::<bool>()
+                .try_invoke();
+            matches!(verified, Ok(Ok(true)))
+        }
+
+        /// Reject a transfer above the sender's co-signing threshold
+        fn check_cosign_threshold(&self, from: &AccountId, amount: TokenBalance) -> Result<()> {
+            if let Some(policy) = self.cosign_policies.get(from) {
+                if amount > policy.threshold {
+                    return Err(Error::CosignRequired);
+                }
+            }
+            Ok(())
+        }
+
+        /// Count an outgoing transfer against the sender's daily limit
+        fn record_spending(&mut self, from: AccountId, amount: TokenBalance) {
+            if let Some((limit, effective_at)) = self.pending_spending_limits.get(from) {
+                if effective_at <= self.env().block_timestamp() {
+                    self.spending_limits.insert(from, &limit);
+                    self.pending_spending_limits.remove(from);
+                }
+            }
+            if self.spending_limits.contains(from) {
+                let today = self.env().block_timestamp() / DAY;
+                let spent = self.spent_today(from).saturating_add(amount);
+                self.spending_usage.insert(from, &(today, spent));
+            }
+        }
+
+        /// Single write path for balances, recording the pre-change balance
+        /// for the latest snapshot the first time an account changes after it,
+        /// moving the delegated voting power and notifying the account's
+        /// balance observer
+        fn set_balance(&mut self, account: AccountId, balance: TokenBalance) {
+            let previous = self.balances.get(account).unwrap_or(0);
+            let snapshot_id = self.snapshot_id.get_or_default();
+            if snapshot_id > 0 {
+                let mut snapshots = self.balance_snapshots.get(account).unwrap_or_default();
+                if snapshots.last().map(|(id, _)| *id) != Some(snapshot_id) {
+                    snapshots.push((snapshot_id, previous));
+                    self.balance_snapshots.insert(account, &snapshots);
+                }
+            }
+            self.settle_dividends(account, previous);
+            self.balances.insert(account, &balance);
+            if previous == 0 && balance > 0 {
+                self.add_holder(account);
+            } else if previous > 0 && balance == 0 {
+                self.remove_holder(account);
+            }
+            if let Some(delegate) = self.delegates.get(account) {
+                if balance > previous {
+                    self.move_votes(None, Some(delegate), balance.saturating_sub(previous));
+                } else {
+                    self.move_votes(Some(delegate), None, previous.saturating_sub(balance));
+                }
+            }
+            if let Some(observer) = self.balance_observers.get(account) {
+                // best effort: a failing or reverting observer never blocks the change
+                let _ = build_call::<Environment>()
+                    .call(observer)
+                    .ref_time_limit(NOTIFY_REF_TIME_LIMIT)
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(ON_BALANCE_CHANGED))
+                            .push_arg(account)
+                            .push_arg(previous)
+                            .push_arg(balance),
+                    )
+                    .returns::<()>()
+                    .try_invoke();
+            }
+        }
+
+        /// Bring an account's dividends up to the current index, crediting
+        /// what `balance` earned since its last update. Custody never earns.
+        fn settle_dividends(&mut self, account: AccountId, balance: TokenBalance) {
+            let index = self.dividend_index.get_or_default();
+            if index == 0 || account == self.env().account_id() {
+                return;
+            }
+            let mut record = self.dividend_accounts.get(account).unwrap_or_default();
+            if record.index == index {
+                return;
+            }
+            record.accrued = record.accrued_at(balance, index);
+            record.index = index;
+            self.dividend_accounts.insert(account, &record);
+        }
+
+        /// Deposit `amount` of the caller's tokens as dividends, shared pro
+        /// rata by everyone holding tokens outside contract custody
+        #[ink(message)]
+        pub fn distribute(&mut self, amount: TokenBalance) -> Result<()> {
+            let caller = self.env().caller();
+            let custody = self.env().account_id();
+            self.check_sender(&caller, amount)?;
+            self.debit(caller, amount)?;
+            self.deliver(caller, custody, amount)?;
+            let eligible = self
+                .total_supply
+                .get_or_default()
+                .saturating_sub(self.balances.get(custody).unwrap_or(0));
+            if eligible == 0 {
+                return Err(Error::NoDividendRecipients);
+            }
+            let increase = mul_div(amount, DIVIDEND_MAGNITUDE, eligible).ok_or(Error::Overflow)?;
+            let index = self
+                .dividend_index
+                .get_or_default()
+                .checked_add(increase)
+                .ok_or(Error::Overflow)?;
+            self.dividend_index.set(&index);
+            self.env().emit_event(DividendsDistributed {
+                from: caller,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Pay out the caller's accrued dividends from custody
+        #[ink(message)]
+        pub fn withdraw_dividend(&mut self) -> Result<TokenBalance> {
+            let caller = self.env().caller();
+            let balance = self.balances.get(caller).unwrap_or(0);
+            self.settle_dividends(caller, balance);
+            let mut record = self.dividend_accounts.get(caller).unwrap_or_default();
+            let amount = record.accrued;
+            if amount > 0 {
+                record.accrued = 0;
+                self.dividend_accounts.insert(caller, &record);
+                let custody = self.env().account_id();
+                self.debit(custody, amount)?;
+                self.deliver(custody, caller, amount)?;
+                self.env().emit_event(DividendWithdrawn {
+                    account: caller,
+                    amount,
+                });
+            }
+            Ok(amount)
+        }
+
+        /// Dividends `account` can withdraw now
+        #[ink(message)]
+        pub fn withdrawable_dividend_of(&self, account: AccountId) -> TokenBalance {
+            if account == self.env().account_id() {
+                return 0;
+            }
+            let balance = self.balances.get(account).unwrap_or(0);
+            self.dividend_accounts
+                .get(account)
+                .unwrap_or_default()
+                .accrued_at(balance, self.dividend_index.get_or_default())
+        }
+
+        /// Append `account` to the holder registry
+        fn add_holder(&mut self, account: AccountId) {
+            let count = self.holder_count.get_or_default();
+            self.holders.insert(count, &account);
+            self.holder_index.insert(account, &count);
+            self.holder_count.set(&count.saturating_add(1));
+        }
+
+        /// Drop `account` from the holder registry, moving the last holder
+        /// into its slot
+        fn remove_holder(&mut self, account: AccountId) {
+            let Some(index) = self.holder_index.take(account) else {
+                return;
+            };
+            let last = self.holder_count.get_or_default().saturating_sub(1);
+            if index != last {
+                if let Some(moved) = self.holders.get(last) {
+                    self.holders.insert(index, &moved);
+                    self.holder_index.insert(moved, &index);
+                }
+            }
+            self.holders.remove(last);
+            self.holder_count.set(&last);
+        }
+
+        /// Number of accounts with a non-zero balance
+        #[ink(message)]
+        pub fn holder_count(&self) -> u32 {
+            self.holder_count.get_or_default()
+        }
+
+        /// Page of accounts with a non-zero balance, in no particular order
+        #[ink(message)]
+        pub fn holders(&self, offset: u32, limit: u32) -> Vec<AccountId> {
+            let end = offset.saturating_add(limit).min(self.holder_count());
+            (offset..end).filter_map(|index| self.holders.get(index)).collect()
+        }
+
+        /// Stored balance minus demurrage accrued since it was last settled
+        fn current_balance(&self, account: &AccountId) -> TokenBalance {
+            let balance = self.balances.get(account).unwrap_or(0);
+            balance.saturating_sub(self.pending_demurrage(account, balance))
+        }
+
+        /// Decay accrued on `balance` since the account's last checkpoint.
+        /// Decay is linear between checkpoints; every balance change settles it.
+        fn pending_demurrage(&self, account: &AccountId, balance: TokenBalance) -> TokenBalance {
+            let Some(config) = self.demurrage.get().flatten() else {
+                return 0;
+            };
+            // the contract's own custody balance never decays
+            if config.sink == Some(*account)
+                || *account == self.env().account_id()
+                || config.period == 0
+            {
+                return 0;
+            }
+            let Some(since) = self.demurrage_checkpoints.get(account) else {
+                return 0;
+            };
+            let elapsed = u128::from(self.env().block_timestamp().saturating_sub(since));
+            mul_div(bps_of(balance, config.rate_bps), elapsed, u128::from(config.period))
+                .unwrap_or(balance)
+                .min(balance)
+        }
+
+        /// Apply accrued demurrage before an account's balance changes,
+        /// routing the decayed tokens to the sink or burning them
+        fn settle_demurrage(&mut self, account: AccountId) -> Result<()> {
+            let Some(config) = self.demurrage.get().flatten() else {
+                return Ok(());
+            };
+            let balance = self.balances.get(account).unwrap_or(0);
+            let decay = self.pending_demurrage(&account, balance);
+            self.demurrage_checkpoints.insert(account, &self.env().block_timestamp());
+            if decay == 0 {
+                return Ok(());
+            }
+            let balance = balance.checked_sub(decay).ok_or(Error::Overflow)?;
+            self.set_balance(account, balance);
+            match config.sink {
+                Some(sink) => {
+                    let sink_balance = self.balances.get(sink).unwrap_or(0);
+                    let sink_balance = sink_balance.checked_add(decay).ok_or(Error::Overflow)?;
+                    self.set_balance(sink, sink_balance);
+                    self.env().emit_event(Transfer {
+                        from: account,
+                        to: sink,
+                        amount: decay,
+                    });
+                }
+                None => self.reduce_supply(account, decay)?,
+            }
+            Ok(())
+        }
+
+        /// Balance an account can send or burn
+        fn spendable_balance(&self, account: &AccountId) -> TokenBalance {
+            let balance = self
+                .current_balance(account)
+                .saturating_sub(self.held_balance_of(*account));
+            if self.sub_accounts_restricted.get(account).unwrap_or(false) {
+                balance.saturating_sub(self.allocated_balance(account))
+            } else {
+                balance
+            }
+        }
+
+        /// Total earmarked in an account's sub-accounts
+        fn allocated_balance(&self, account: &AccountId) -> TokenBalance {
+            Self::sum_sub_accounts(&self.sub_accounts.get(account).unwrap_or_default())
+        }
+
+        fn sum_sub_accounts(buckets: &SubAccounts) -> TokenBalance {
+            buckets
+                .iter()
+                .fold(0u128, |total, (_, amount)| total.saturating_add(*amount))
+        }
+
+        /// Take `amount` out of `account`. Unless the account restricts its
+        /// sub-accounts, earmarks that no longer fit the balance are trimmed.
+        fn debit(&mut self, account: AccountId, amount: TokenBalance) -> Result<()> {
+            self.settle_demurrage(account)?;
+            if self.spendable_balance(&account) < amount {
+                return Err(Error::InsufficientBalance);
+            }
+            let balance = self
+                .balances
+                .get(account)
+                .unwrap_or(0)
+                .checked_sub(amount)
+                .ok_or(Error::InsufficientBalance)?;
+            self.set_balance(account, balance);
+
+            if let Some(mut plan) = self.inheritance.get(account) {
+                plan.last_active = self.env().block_timestamp();
+                self.inheritance.insert(account, &plan);
+            }
+
+            if let Some(mut buckets) = self.sub_accounts.get(account) {
+                let mut excess = Self::sum_sub_accounts(&buckets).saturating_sub(balance);
+                if excess > 0 {
+                    for (label, bucket) in buckets.iter_mut() {
+                        let cut = excess.min(*bucket);
+                        *bucket = bucket.saturating_sub(cut);
+                        excess = excess.saturating_sub(cut);
+                        self.env().emit_event(SubBalanceChanged {
+                            account,
+                            label: *label,
+                            balance: *bucket,
+                        });
+                        if excess == 0 {
+                            break;
+                        }
+                    }
+                    buckets.retain(|(_, bucket)| *bucket > 0);
+                    self.sub_accounts.insert(account, &buckets);
+                }
+            }
+            Ok(())
+        }
+
+        /// Credit `amount` to `account`
+        fn credit(&mut self, account: AccountId, amount: TokenBalance) -> Result<()> {
+            self.settle_demurrage(account)?;
+            let balance = self.balances.get(account).unwrap_or(0);
+            let balance = balance.checked_add(amount).ok_or(Error::Overflow)?;
+            self.set_balance(account, balance);
+            Ok(())
+        }
+
+        /// Move tokens, routing the donation share (if any) to the charity
+        fn transfer_internal(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            amount: TokenBalance,
+        ) -> Result<()> {
+            self.debit(from, amount)?;
+            self.record_spending(from, amount);
+            self.deliver_quoted(from, to, amount)
+        }
+
+        /// Deliver an amount already debited from `from`, routing the donation
+        /// share (if any) to the charity and the fee (if any) to the treasury
+        /// and burning the burn-on-transfer share (if any)
+        fn deliver_quoted(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            amount: TokenBalance,
+        ) -> Result<()> {
+            let quote = self.quote(&from, &to, amount);
+            if let Some(charity) = quote.charity {
+                if quote.donation > 0 {
+                    self.deliver(from, charity, quote.donation)?;
+                    let total = self.total_donated.get_or_default();
+                    self.total_donated.set(&total.saturating_add(quote.donation));
+                }
+            }
+            if let Some(treasury) = quote.treasury {
+                if quote.fee > 0 {
+                    self.deliver(from, treasury, quote.fee)?;
+                    self.note_treasury_inflow(from, treasury, quote.fee)?;
+                    self.env().emit_event(FeeCharged {
+                        from,
+                        treasury,
+                        amount: quote.fee,
+                    });
+                }
+            }
+            if quote.burned > 0 {
+                self.reduce_supply(from, quote.burned)?;
+            }
+
+            self.deliver(from, to, quote.net)
+        }
+
+        /// Split a transfer of `amount` from `from` into deductions and the
+        /// amount delivered
+        fn quote(&self, from: &AccountId, to: &AccountId, amount: TokenBalance) -> FeeQuote {
+            let config = self.donation.get_or_default();
+            let donation = match config.charity {
+                Some(_) if !self.donation_opt_out.get(from).unwrap_or(false) => {
+                    bps_of(amount, config.bps)
+                }
+                _ => 0,
+            };
+            let treasury = self.treasury.get().flatten();
+            let fee = match treasury {
+                Some(_) if self.fee_applies(from, to) => {
+                    bps_of(amount, self.fee_bps.get_or_default())
+                        .min(amount.saturating_sub(donation))
+                }
+                _ => 0,
+            };
+            let remaining = amount.saturating_sub(donation).saturating_sub(fee);
+            let burned = bps_of(amount, self.burn_rate_bps.get_or_default()).min(remaining);
+            FeeQuote {
+                amount,
+                donation,
+                charity: config.charity,
+                fee,
+                treasury,
+                burned,
+                net: remaining.saturating_sub(burned),
+            }
+        }
+
+        /// Whether a transfer between `from` and `to` pays the transfer fee.
+        /// Single hook point for fee exemption rules: currently a transfer is
+        /// exempt when either side is on the exempt list or is the treasury.
+        fn fee_applies(&self, from: &AccountId, to: &AccountId) -> bool {
+            let treasury = self.treasury.get().flatten();
+            [from, to]
+                .iter()
+                .all(|account| Some(**account) != treasury && !self.fee_exempt.contains(*account))
+        }
+
+        /// Credit one leg of a transfer whose amount was already debited from
+        /// `from`, updating both accounts' stats
+        fn deliver(&mut self, from: AccountId, to: AccountId, amount: TokenBalance) -> Result<()> {
+            self.call_transfer_hooks(BEFORE_TRANSFER, Some(from), Some(to), amount)?;
+            self.credit(to, amount)?;
+
+            let mut sender = self.account_stats.get(from).unwrap_or_default();
+            sender.sent = sender.sent.saturating_add(amount);
+            sender.sends = sender.sends.saturating_add(1);
+            self.account_stats.insert(from, &sender);
+
+            let mut recipient = self.account_stats.get(to).unwrap_or_default();
+            recipient.received = recipient.received.saturating_add(amount);
+            recipient.receives = recipient.receives.saturating_add(1);
+            self.account_stats.insert(to, &recipient);
+
+            self.env().emit_event(Transfer { from, to, amount });
+            self.call_transfer_hooks(AFTER_TRANSFER, Some(from), Some(to), amount)
+        }
+
+        /// Mint tokens (owner or grantee)
+        #[ink(message)]
+        pub fn mint(&mut self, to: AccountId, amount: TokenBalance) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("mint"))?;
+            self.mint_internal(to, amount, None)
+        }
+
+        /// Mint against an off-chain reference such as a fiat payment id or a
+        /// bridge message hash (owner or grantee). Each reference mints at most once.
+        #[ink(message)]
+        pub fn mint_with_reference(
+            &mut self,
+            to: AccountId,
+            amount: TokenBalance,
+            reference: [u8; 32],
+        ) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("mint_with_reference"))?;
+            if self.mint_references.contains(reference) {
+                return Err(Error::ReferenceAlreadyUsed);
+            }
+            self.mint_references.insert(reference, &());
+            self.mint_internal(to, amount, Some(reference))
+        }
+
+        /// Whether a mint reference was already used
+        #[ink(message)]
+        pub fn is_mint_reference_used(&self, reference: [u8; 32]) -> bool {
+            self.mint_references.contains(reference)
+        }
+
+        /// Mint against a voucher the owner signed off-chain; the recipient (or
+        /// anyone) submits it and pays the fees. Each voucher id mints once.
+        #[ink(message)]
+        pub fn redeem_mint_voucher(
+            &mut self,
+            to: AccountId,
+            amount: TokenBalance,
+            voucher_id: u64,
+            expiry: Timestamp,
+            owner_signature: [u8; 65],
+        ) -> Result<()> {
+            if self.env().block_timestamp() > expiry {
+                return Err(Error::Expired);
+            }
+            if self.used_mint_vouchers.contains(voucher_id) {
+                return Err(Error::VoucherUsed);
+            }
+            let payload = self.mint_voucher_payload(to, amount, voucher_id, expiry);
+            if !self.is_signed_by(&self.owner, &payload, &owner_signature) {
+                return Err(Error::InvalidSignature);
+            }
+            self.used_mint_vouchers.insert(voucher_id, &());
+            self.mint_internal(to, amount, None)
+        }
+
+        /// Bytes the owner signs (ECDSA) to issue a mint voucher
+        #[ink(message)]
+        pub fn mint_voucher_payload(
+            &self,
+            to: AccountId,
+            amount: TokenBalance,
+            voucher_id: u64,
+            expiry: Timestamp,
+        ) -> Vec<u8> {
+            ink::scale::Encode::encode(&(
+                MINT_VOUCHER_DOMAIN,
+                self.env().account_id(),
+                to,
+                amount,
+                voucher_id,
+                expiry,
+            ))
+        }
+
+        /// Whether a mint voucher id was already redeemed
+        #[ink(message)]
+        pub fn is_mint_voucher_used(&self, voucher_id: u64) -> bool {
+            self.used_mint_vouchers.contains(voucher_id)
+        }
+
+        /// Whether `signature` is an ECDSA signature by `signer` over `payload`.
+        /// As with Substrate ECDSA keys, the signed digest is the blake2-256 of
+        /// the payload and the account id is the blake2-256 of the public key.
+        fn is_signed_by(&self, signer: &AccountId, payload: &[u8], signature: &[u8; 65]) -> bool {
+            let mut digest = [0u8; 32];
+            ink::env::hash_bytes::<Blake2x256>(payload, &mut digest);
+            let Ok(public_key) = self.env().ecdsa_recover(signature, &digest) else {
+                return false;
+            };
+            let mut account = [0u8; 32];
+            ink::env::hash_bytes::<Blake2x256>(&public_key, &mut account);
+            AccountId::from(account) == *signer
+        }
+
+        /// Create `amount` new tokens for `to`
+        fn mint_internal(
+            &mut self,
+            to: AccountId,
+            amount: TokenBalance,
+            reference: Option<[u8; 32]>,
+        ) -> Result<()> {
+            self.call_transfer_hooks(BEFORE_TRANSFER, None, Some(to), amount)?;
+            let new_total = self.increase_supply(amount)?;
+            self.credit(to, amount)?;
+            self.env().emit_event(Mint {
+                to,
+                amount,
+                reference,
+            });
+            self.env().emit_event(SupplyIncreased {
+                by: amount,
+                new_total,
+            });
+            self.call_transfer_hooks(AFTER_TRANSFER, None, Some(to), amount)
+        }
+
+        /// Grow the total supply by `amount` to be credited by the caller,
+        /// enforcing the minting pause and the cap. Returns the new total.
+        fn increase_supply(&mut self, amount: TokenBalance) -> Result<TokenBalance> {
+            if self.minting_paused.get_or_default() {
+                return Err(Error::MintingPaused);
+            }
+            let previous_total = self.total_supply.get_or_default();
+            let new_total = previous_total.checked_add(amount).ok_or(Error::Overflow)?;
+            if self.max_supply.get().is_some_and(|max| new_total > max) {
+                return Err(Error::MaxSupplyExceeded);
+            }
+            self.total_supply.set(&new_total);
+            self.notify_supply_observers(previous_total, new_total);
+            Ok(new_total)
+        }
+
+        /// Destroy `amount` of `from`'s tokens
+        fn burn_internal(&mut self, from: AccountId, amount: TokenBalance) -> Result<()> {
+            if self.burning_paused.get_or_default() {
+                return Err(Error::BurningPaused);
+            }
+            self.debit(from, amount)?;
+            self.reduce_supply(from, amount)?;
+            Ok(())
+        }
+
+        /// Count `amount` just delivered from `from` to `to` towards the
+        /// contract-managed treasury when `to` is this contract
+        fn note_treasury_inflow(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            amount: TokenBalance,
+        ) -> Result<()> {
+            if to != self.env().account_id() || amount == 0 {
+                return Ok(());
+            }
+            let held = self.treasury_held.get_or_default();
+            self.treasury_held
+                .set(&held.checked_add(amount).ok_or(Error::Overflow)?);
+            self.env().emit_event(TreasuryDeposit { from, amount });
+            Ok(())
+        }
+
+        /// Native balance not backing redemptions or an open sale
+        fn spare_native(&self) -> Balance {
+            let mut reserved = self.redemption.get_or_default().reserve;
+            if let Some(sale) = self.sale().filter(|sale| !sale.finalized) {
+                reserved = reserved.saturating_add(sale.raised);
+            }
+            self.env().balance().saturating_sub(reserved)
+        }
+
+        /// Account for `amount` of `from`'s already debited tokens being destroyed
+        fn reduce_supply(&mut self, from: AccountId, amount: TokenBalance) -> Result<()> {
+            self.call_transfer_hooks(BEFORE_TRANSFER, Some(from), None, amount)?;
+            let previous_total = self.total_supply.get_or_default();
+            let new_total = previous_total.checked_sub(amount).ok_or(Error::Overflow)?;
+            self.total_supply.set(&new_total);
+            self.notify_supply_observers(previous_total, new_total);
+            self.env().emit_event(Burn { from, amount });
+            self.env().emit_event(SupplyDecreased {
+                by: amount,
+                new_total,
+            });
+            self.call_transfer_hooks(AFTER_TRANSFER, Some(from), None, amount)
+        }
+
+        /// Call `selector` on every transfer hook; any failure aborts the
+        /// balance change. Hooks are called without the re-entry flag, so
+        /// they cannot call back into this contract.
+        fn call_transfer_hooks(
+            &self,
+            selector: [u8; 4],
+            from: Option<AccountId>,
+            to: Option<AccountId>,
+            amount: TokenBalance,
+        ) -> Result<()> {
+            let operator = self.env().caller();
+            for hook in self.transfer_hooks.get_or_default() {
+                let result = build_call::<Environment>()
+                    .call(hook)
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(selector))
+                            .push_arg(operator)
+                            .push_arg(from)
+                            .push_arg(to)
+                            .push_arg(amount),
+                    )
+                    .returns::<core::result::Result<(), PSP22Error>>()
+                    .try_invoke();
+                if !matches!(result, Ok(Ok(Ok(())))) {
+                    return Err(Error::HookRejected);
+                }
+            }
+            Ok(())
+        }
+
+        /// Best-effort notification of every supply observer
+        fn notify_supply_observers(&self, previous_total: TokenBalance, new_total: TokenBalance) {
+            for observer in self.supply_observers.get_or_default() {
+                // a failing or reverting observer never blocks the mint or burn
+                let _ = build_call::<Environment>()
+                    .call(observer)
+                    .ref_time_limit(NOTIFY_REF_TIME_LIMIT)
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(ON_SUPPLY_CHANGED))
+                            .push_arg(previous_total)
+                            .push_arg(new_total),
+                    )
+                    .returns::<()>()
+                    .try_invoke();
+            }
+        }
+
+        /// Burn own tokens
+        #[ink(message)]
+        pub fn burn(&mut self, amount: TokenBalance) -> Result<()> {
+            let caller = self.env().caller();
+            self.burn_internal(caller, amount)
+        }
+
+        /// Total token supply
+        #[ink(message)]
+        pub fn total_supply(&self) -> TokenBalance {
+            PSP22::total_supply(self)
+        }
+
+        /// Allow `burner` to destroy up to `amount` of the caller's tokens.
+        /// Separate from the transfer allowance: a burner cannot move tokens.
+        #[ink(message)]
+        pub fn approve_burn(&mut self, burner: AccountId, amount: TokenBalance) -> Result<()> {
+            let caller = self.env().caller();
+            self.burn_allowances.insert((caller, burner), &amount);
+            self.env().emit_event(BurnApproval {
+                owner: caller,
+                burner,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Burn allowance query
+        #[ink(message)]
+        pub fn burn_allowance(&self, owner: AccountId, burner: AccountId) -> TokenBalance {
+            self.burn_allowances.get((owner, burner)).unwrap_or(0)
+        }
+
+        /// Burn tokens of `from` using the caller's burn allowance
+        #[ink(message)]
+        pub fn burn_from_approved(&mut self, from: AccountId, amount: TokenBalance) -> Result<()> {
+            let caller = self.env().caller();
+            let allowance = self.burn_allowance(from, caller);
+            if allowance < amount {
+                return Err(Error::AllowanceTooLow);
+            }
+            self.burn_internal(from, amount)?;
+            self.burn_allowances.insert((from, caller), &(allowance.saturating_sub(amount)));
+            Ok(())
+        }
+
+        /// Burn `amount` of `from`'s tokens, spending the caller's regular
+        /// transfer allowance
+        #[ink(message)]
+        pub fn burn_from(&mut self, from: AccountId, amount: TokenBalance) -> Result<()> {
+            let caller = self.env().caller();
+            let allowance = self.live_allowance(from, caller)?;
+            if allowance < amount {
+                return Err(Error::AllowanceTooLow);
+            }
+            self.burn_internal(from, amount)?;
+            self.allowances.insert((from, caller), &(allowance.saturating_sub(amount)));
+            Ok(())
+        }
+
+        /// Read balance
+        #[ink(message)]
+        pub fn balance_of(&self, owner: AccountId) -> TokenBalance {
+            PSP22::balance_of(self, owner)
+        }
+
+        /// Balances of many accounts, in input order
+        #[ink(message)]
+        pub fn balances_of(&self, accounts: Vec<AccountId>) -> Vec<TokenBalance> {
+            accounts
+                .into_iter()
+                .map(|account| self.balance_of(account))
+                .collect()
+        }
+
+        /// Transfer
+        #[ink(message, payable)]
+        pub fn transfer(&mut self, to: AccountId, amount: TokenBalance) -> Result<()> {
+            self.collect_native_fee()?;
+            self.send(to, amount)
+        }
+
+        /// Checked transfer from the caller, without fee collection
+        fn send(&mut self, to: AccountId, amount: TokenBalance) -> Result<()> {
+            let caller = self.effective_caller();
+            if self.is_noop_transfer(&caller, &to, amount)? {
+                return Ok(());
+            }
+            self.can_transfer(&caller, &to, amount)?;
+            self.transfer_internal(caller, to, amount)
+        }
+
+        /// Apply the transfer guards: fails on a rejected degenerate transfer,
+        /// `Ok(true)` for one that should succeed without doing anything
+        fn is_noop_transfer(
+            &self,
+            from: &AccountId,
+            to: &AccountId,
+            amount: TokenBalance,
+        ) -> Result<bool> {
+            let guards = self.transfer_guards.get_or_default();
+            if *to == AccountId::from([0u8; 32]) && !guards.allow_zero_address {
+                return Err(Error::ZeroRecipient);
+            }
+            if *to == self.env().account_id() && !guards.allow_to_contract {
+                return Err(Error::RecipientIsContract);
+            }
+            if amount == 0 {
+                return if guards.reject_zero_amount {
+                    Err(Error::ZeroAmount)
+                } else {
+                    Ok(true)
+                };
+            }
+            if from == to {
+                return if guards.reject_self_transfer {
+                    Err(Error::SelfTransfer)
+                } else {
+                    Ok(true)
+                };
+            }
+            Ok(false)
+        }
+
+        /// Configure the handling of degenerate transfers (owner or grantee)
+        #[ink(message)]
+        pub fn set_transfer_guards(&mut self, guards: TransferGuards) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_transfer_guards"))?;
+            self.transfer_guards.set(&guards);
+            Ok(())
+        }
+
+        /// Current handling of degenerate transfers
+        #[ink(message)]
+        pub fn transfer_guards(&self) -> TransferGuards {
+            self.transfer_guards.get_or_default()
+        }
+
+        /// Transfer carrying `data`. If `to` is a contract its
+        /// `PSP22Receiver::before_received` hook is called first, and the
+        /// transfer fails unless the hook accepts it.
+        #[ink(message, payable)]
+        pub fn transfer_with_data(
+            &mut self,
+            to: AccountId,
+            amount: TokenBalance,
+            data: Vec<u8>,
+        ) -> Result<()> {
+            self.collect_native_fee()?;
+            let caller = self.env().caller();
+            if self.is_noop_transfer(&caller, &to, amount)? {
+                return Ok(());
+            }
+            self.can_transfer(&caller, &to, amount)?;
+            self.ensure_received(caller, caller, to, amount, data)?;
+            self.transfer_internal(caller, to, amount)
+        }
+
+        /// Transfer tagged with a memo of at most `MAX_MEMO_LEN` bytes, e.g. an
+        /// exchange deposit reference, published in a `TransferWithMemo` event
+        #[ink(message, payable)]
+        pub fn transfer_with_memo(
+            &mut self,
+            to: AccountId,
+            amount: TokenBalance,
+            memo: Vec<u8>,
+        ) -> Result<()> {
+            if memo.len() > MAX_MEMO_LEN {
+                return Err(Error::MemoTooLong);
+            }
+            self.collect_native_fee()?;
+            let from = self.env().caller();
+            self.send(to, amount)?;
+            self.env().emit_event(TransferWithMemo {
+                from,
+                to,
+                amount,
+                memo,
+            });
+            Ok(())
+        }
+
+        /// Ask a recipient contract whether it accepts a transfer. Plain
+        /// accounts always accept; a contract that traps, lacks the hook or
+        /// returns an error rejects.
+        fn ensure_received(
+            &self,
+            operator: AccountId,
+            from: AccountId,
+            to: AccountId,
+            amount: TokenBalance,
+            data: Vec<u8>,
+        ) -> Result<()> {
+            if !self.env().is_contract(&to) {
+                return Ok(());
+            }
+            let accepted = build_call::<Environment>()
+                .call(to)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(PSP22_BEFORE_RECEIVED))
+                        .push_arg(operator)
+                        .push_arg(from)
+                        .push_arg(amount)
+                        .push_arg(data),
+                )
+                .returns::<core::result::Result<(), PSP22ReceiverError>>()
+                .try_invoke();
+            match accepted {
+                Ok(Ok(Ok(()))) => Ok(()),
+                _ => Err(Error::TransferRejected),
+            }
+        }
+
+        /// Lifetime transfer totals of an account
+        #[ink(message)]
+        pub fn account_stats(&self, account: AccountId) -> AccountStats {
+            self.account_stats.get(account).unwrap_or_default()
+        }
+
+        /// Dry-run a transfer: runs every restriction check and returns the
+        /// fee breakdown, or the error the transfer would fail with
+        #[ink(message)]
+        pub fn can_transfer_query(
+            &self,
+            from: AccountId,
+            to: AccountId,
+            amount: TokenBalance,
+        ) -> Result<FeeQuote> {
+            self.can_transfer(&from, &to, amount)?;
+            if self.spendable_balance(&from) < amount {
+                return Err(Error::InsufficientBalance);
+            }
+            Ok(self.quote(&from, &to, amount))
+        }
+
+        /// Approve spender
+        #[ink(message)]
+        pub fn approve(&mut self, spender: AccountId, amount: TokenBalance) -> Result<()> {
+            let caller = self.env().caller();
+            self.approve_until(caller, spender, amount, None)
+        }
+
+        /// Approve `spender` until `expires_at`; from then on the allowance
+        /// reads as zero and cannot be spent. Increasing or decreasing the
+        /// allowance keeps the deadline, a plain `approve` clears it.
+        #[ink(message)]
+        pub fn approve_with_deadline(
+            &mut self,
+            spender: AccountId,
+            amount: TokenBalance,
+            expires_at: Timestamp,
+        ) -> Result<()> {
+            if expires_at <= self.env().block_timestamp() {
+                return Err(Error::Expired);
+            }
+            let caller = self.env().caller();
+            self.approve_until(caller, spender, amount, Some(expires_at))
+        }
+
+        /// Let `operator` move any amount of the caller's tokens through
+        /// `transfer_from`, without drawing on an allowance
+        #[ink(message)]
+        pub fn set_operator(&mut self, operator: AccountId, approved: bool) -> Result<()> {
+            let caller = self.env().caller();
+            if approved {
+                self.operators.insert((caller, operator), &());
+            } else {
+                self.operators.remove((caller, operator));
+            }
+            self.env().emit_event(OperatorUpdated {
+                owner: caller,
+                operator,
+                approved,
+            });
+            Ok(())
+        }
+
+        /// Whether `operator` may move any amount of `owner`'s tokens
+        #[ink(message)]
+        pub fn is_operator(&self, owner: AccountId, operator: AccountId) -> bool {
+            self.operators.contains((owner, operator))
+        }
+
+        /// Let `spender` pull up to `amount_per_period` through `transfer_from`
+        /// in every period of `period_length` ms, starting now. While set it
+        /// replaces the regular allowance; zero `amount_per_period` revokes it.
+        #[ink(message)]
+        pub fn approve_rate_limited(
+            &mut self,
+            spender: AccountId,
+            amount_per_period: TokenBalance,
+            period_length: Timestamp,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            if amount_per_period == 0 {
+                self.rate_limited_allowances.remove((caller, spender));
+            } else {
+                if period_length == 0 {
+                    return Err(Error::InvalidPeriod);
+                }
+                self.rate_limited_allowances.insert(
+                    (caller, spender),
+                    &RateLimitedAllowance {
+                        amount_per_period,
+                        period: period_length,
+                        period_start: self.env().block_timestamp(),
+                        spent: 0,
+                    },
+                );
+            }
+            self.env().emit_event(RateLimitedApproval {
+                owner: caller,
+                spender,
+                amount_per_period,
+                period: period_length,
+            });
+            Ok(())
+        }
+
+        /// Rate-limited approval of `spender` on `owner`'s tokens
+        #[ink(message)]
+        pub fn rate_limited_allowance(
+            &self,
+            owner: AccountId,
+            spender: AccountId,
+        ) -> Option<RateLimitedAllowance> {
+            self.rate_limited_allowances.get((owner, spender))
+        }
+
+        /// Amount `spender` can still pull from `owner` in the current period
+        #[ink(message)]
+        pub fn rate_limited_remaining(&self, owner: AccountId, spender: AccountId) -> TokenBalance {
+            self.rate_limited_allowances
+                .get((owner, spender))
+                .map(|mut limit| {
+                    limit.roll(self.env().block_timestamp());
+                    limit.remaining()
+                })
+                .unwrap_or(0)
+        }
+
+        /// Deadline of an allowance, `None` if it never expires
+        #[ink(message)]
+        pub fn allowance_expiry(&self, owner: AccountId, spender: AccountId) -> Option<Timestamp> {
+            self.allowance_expiries.get((owner, spender))
+        }
+
+        /// Replace an allowance together with its deadline
+        fn approve_until(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            amount: TokenBalance,
+            expires_at: Option<Timestamp>,
+        ) -> Result<()> {
+            self.set_allowance(owner, spender, amount)?;
+            match expires_at {
+                Some(expires_at) => {
+                    self.allowance_expiries.insert((owner, spender), &expires_at);
+                }
+                None => self.allowance_expiries.remove((owner, spender)),
+            }
+            Ok(())
+        }
+
+        /// Allowance `spender` may draw on right now, failing once expired
+        fn live_allowance(&self, owner: AccountId, spender: AccountId) -> Result<TokenBalance> {
+            if self
+                .allowance_expiries
+                .get((owner, spender))
+                .is_some_and(|expires_at| self.env().block_timestamp() >= expires_at)
+            {
+                return Err(Error::AllowanceExpired);
+            }
+            Ok(self.allowances.get((owner, spender)).unwrap_or(0))
+        }
+
+        /// Single write path for owner-granted allowances; any increase counts
+        /// against the owner's daily approval limit
+        fn set_allowance(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            amount: TokenBalance,
+        ) -> Result<()> {
+            let increase = amount.saturating_sub(self.allowance(owner, spender));
+            if increase > 0 {
+                self.record_approval(owner, increase)?;
+            }
+            self.allowances.insert((owner, spender), &amount);
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Promote a due pending approval limit and count `increase` against
+        /// today's budget
+        fn record_approval(&mut self, owner: AccountId, increase: TokenBalance) -> Result<()> {
+            if let Some((limit, effective_at)) = self.pending_approval_limits.get(owner) {
+                if effective_at <= self.env().block_timestamp() {
+                    self.approval_limits.insert(owner, &limit);
+                    self.pending_approval_limits.remove(owner);
+                }
+            }
+            if let Some(limit) = self.approval_limits.get(owner) {
+                let approved = self.approved_today(owner).saturating_add(increase);
+                if approved > limit.max_per_day {
+                    return Err(Error::ExceedsApprovalLimit);
+                }
+                let today = self.env().block_timestamp() / DAY;
+                self.approval_usage.insert(owner, &(today, approved));
+            }
+            Ok(())
+        }
+
+        /// Allowance query
+        #[ink(message)]
+        pub fn allowance(&self, owner: AccountId, spender: AccountId) -> TokenBalance {
+            PSP22::allowance(self, owner, spender)
+        }
+
+        /// Raise the caller's allowance for `spender` by `delta`
+        #[ink(message)]
+        pub fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta: TokenBalance,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            let allowance = self
+                .allowance(caller, spender)
+                .checked_add(delta)
+                .ok_or(Error::Overflow)?;
+            self.set_allowance(caller, spender, allowance)
+        }
+
+        /// Lower the caller's allowance for `spender` by `delta`, failing
+        /// rather than wrapping if `delta` exceeds it
+        #[ink(message)]
+        pub fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta: TokenBalance,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            let allowance = self
+                .allowance(caller, spender)
+                .checked_sub(delta)
+                .ok_or(Error::AllowanceTooLow)?;
+            self.set_allowance(caller, spender, allowance)
+        }
+
+        /// Allowances of many (owner, spender) pairs, in input order
+        #[ink(message)]
+        pub fn allowances_batch(&self, pairs: Vec<(AccountId, AccountId)>) -> Vec<TokenBalance> {
+            pairs
+                .into_iter()
+                .map(|(owner, spender)| self.allowance(owner, spender))
+                .collect()
+        }
+
+        /// Transfer from (using allowance)
+        #[ink(message, payable)]
+        pub fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            amount: TokenBalance,
+        ) -> Result<()> {
+            self.collect_native_fee()?;
+            self.transfer_with_allowance(from, to, amount)
+        }
+
+        /// Checked transfer spending the caller's allowance on `from`, without
+        /// fee collection
+        fn transfer_with_allowance(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            amount: TokenBalance,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            if self.is_noop_transfer(&from, &to, amount)? {
+                return Ok(());
+            }
+            self.can_transfer(&from, &to, amount)?;
+            if self.operators.contains((from, caller)) {
+                return self.transfer_internal(from, to, amount);
+            }
+            if let Some(mut limit) = self.rate_limited_allowances.get((from, caller)) {
+                limit.roll(self.env().block_timestamp());
+                if limit.remaining() < amount {
+                    return Err(Error::AllowanceTooLow);
+                }
+                self.transfer_internal(from, to, amount)?;
+                limit.spent = limit.spent.saturating_add(amount);
+                self.rate_limited_allowances.insert((from, caller), &limit);
+                return Ok(());
+            }
+
+            let allowance = self.live_allowance(from, caller)?;
+            if allowance < amount {
+                return Err(Error::AllowanceTooLow);
+            }
+
+            self.transfer_internal(from, to, amount)?;
+
+            // update allowance
+            self.allowances.insert((from, caller), &(allowance.saturating_sub(amount)));
+            let total_spent = self.allowance_spent(from, caller).saturating_add(amount);
+            self.allowance_spent.insert((from, caller), &total_spent);
+            self.env().emit_event(AllowanceSpent {
+                owner: from,
+                spender: caller,
+                amount,
+                total_spent,
+            });
+            if let Some(notifier) = self.approval_notifiers.get(from) {
+                // best effort: a failing or reverting notifier never blocks the transfer
+                let _ = build_call::<Environment>()
+                    .call(notifier)
+                    .ref_time_limit(NOTIFY_REF_TIME_LIMIT)
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(ON_ALLOWANCE_SPENT))
+                            .push_arg(from)
+                            .push_arg(caller)
+                            .push_arg(amount),
+                    )
+                    .returns::<()>()
+                    .try_invoke();
+            }
+            Ok(())
+        }
+
+        /// Register (or clear with `None`) a contract notified whenever one of
+        /// the caller's allowances is spent
+        #[ink(message)]
+        pub fn set_approval_notifier(&mut self, notifier: Option<AccountId>) -> Result<()> {
+            let caller = self.env().caller();
+            match notifier {
+                Some(notifier) => {
+                    self.approval_notifiers.insert(caller, &notifier);
+                }
+                None => self.approval_notifiers.remove(caller),
+            }
+            Ok(())
+        }
+
+        /// Register (or clear with `None`) a contract notified whenever the
+        /// caller's balance changes
+        #[ink(message)]
+        pub fn set_balance_observer(&mut self, observer: Option<AccountId>) -> Result<()> {
+            let caller = self.env().caller();
+            match observer {
+                Some(observer) => {
+                    self.balance_observers.insert(caller, &observer);
+                }
+                None => self.balance_observers.remove(caller),
+            }
+            Ok(())
+        }
+
+        /// Contract notified when an account's balance changes
+        #[ink(message)]
+        pub fn balance_observer(&self, account: AccountId) -> Option<AccountId> {
+            self.balance_observers.get(account)
+        }
+
+        /// Contract notified when an owner's allowances are spent
+        #[ink(message)]
+        pub fn approval_notifier(&self, owner: AccountId) -> Option<AccountId> {
+            self.approval_notifiers.get(owner)
+        }
+
+        /// Total amount a spender has moved out of an owner's account
+        #[ink(message)]
+        pub fn allowance_spent(&self, owner: AccountId, spender: AccountId) -> TokenBalance {
+            self.allowance_spent.get((owner, spender)).unwrap_or(0)
+        }
+
+        /// Pause / Unpause transfers (owner or grantee). The guardian may
+        /// pause but never unpause.
+        #[ink(message)]
+        pub fn set_paused(&mut self, state: bool) -> Result<()> {
+            let caller = self.env().caller();
+            if !(state && self.guardian() == Some(caller)) {
+                self.ensure_permitted(ink::selector_bytes!("set_paused"))?;
+            }
+            self.apply_pause(caller, state, 0, None);
+            Ok(())
+        }
+
+        /// Pause / Unpause transfers with a reason code (owner or grantee)
+        #[ink(message)]
+        pub fn set_paused_with_reason(&mut self, state: bool, reason: u32) -> Result<()> {
+            let caller = self.ensure_permitted(ink::selector_bytes!("set_paused_with_reason"))?;
+            self.apply_pause(caller, state, reason, None);
+            Ok(())
+        }
+
+        /// Set the transfers switch. With `until`, a pause lifts by itself once
+        /// the block timestamp reaches it; any later change replaces it.
+        fn apply_pause(
+            &mut self,
+            caller: AccountId,
+            state: bool,
+            reason: u32,
+            until: Option<Timestamp>,
+        ) {
+            self.paused = state && until.is_none();
+            self.pause_deadline.set(&until.filter(|_| state));
+            self.env().emit_event(PauseSwitched {
+                switch: PauseSwitch::Transfers,
+                paused: state,
+                by: caller,
+            });
+            self.pause_info.set(&PauseInfo {
+                paused: state,
+                by: caller,
+                at: self.env().block_timestamp(),
+                reason,
+            });
+            if state {
+                self.env().emit_event(Paused { by: caller, reason });
+            } else {
+                self.env().emit_event(Unpaused { by: caller, reason });
+            }
+        }
+
+        /// Set (or clear with `None`) the guardian, an emergency brake that
+        /// can only pause transfers through `set_paused(true)` (admin only)
+        #[ink(message)]
+        pub fn set_guardian(&mut self, guardian: Option<AccountId>) -> Result<()> {
+            let caller = self.ensure_admin()?;
+            self.guardian.set(&guardian);
+            self.env().emit_event(GuardianChanged {
+                guardian,
+                by: caller,
+            });
+            Ok(())
+        }
+
+        /// Current guardian
+        #[ink(message)]
+        pub fn guardian(&self) -> Option<AccountId> {
+            self.guardian.get().flatten()
+        }
+
+        /// Pause transfers until the block timestamp reaches `until`, after
+        /// which they resume without further action (owner or grantee)
+        #[ink(message)]
+        pub fn pause_until(&mut self, until: Timestamp) -> Result<()> {
+            let caller = self.ensure_permitted(ink::selector_bytes!("pause_until"))?;
+            if until <= self.env().block_timestamp() {
+                return Err(Error::Expired);
+            }
+            self.apply_pause(caller, true, 0, Some(until));
+            Ok(())
+        }
+
+        /// Whether transfers are paused, either indefinitely or until a
+        /// deadline that has not passed yet
+        #[ink(message)]
+        pub fn is_paused(&self) -> bool {
+            self.paused
+                || self
+                    .pause_deadline
+                    .get()
+                    .flatten()
+                    .is_some_and(|until| self.env().block_timestamp() < until)
+        }
+
+        /// Deadline of the current time-limited pause, if any
+        #[ink(message)]
+        pub fn pause_deadline(&self) -> Option<Timestamp> {
+            self.pause_deadline.get().flatten()
+        }
+
+        /// Pause / Unpause transfers, same as `set_paused` (owner or grantee)
+        #[ink(message)]
+        pub fn pause_transfers(&mut self, state: bool) -> Result<()> {
+            let caller = self.ensure_permitted(ink::selector_bytes!("pause_transfers"))?;
+            self.apply_pause(caller, state, 0, None);
+            Ok(())
+        }
+
+        /// Pause / Unpause every mint path (owner or grantee)
+        #[ink(message)]
+        pub fn pause_minting(&mut self, state: bool) -> Result<()> {
+            let caller = self.ensure_permitted(ink::selector_bytes!("pause_minting"))?;
+            self.minting_paused.set(&state);
+            self.env().emit_event(PauseSwitched {
+                switch: PauseSwitch::Minting,
+                paused: state,
+                by: caller,
+            });
+            Ok(())
+        }
+
+        /// Pause / Unpause burning, including redemptions (owner or grantee)
+        #[ink(message)]
+        pub fn pause_burning(&mut self, state: bool) -> Result<()> {
+            let caller = self.ensure_permitted(ink::selector_bytes!("pause_burning"))?;
+            self.burning_paused.set(&state);
+            self.env().emit_event(PauseSwitched {
+                switch: PauseSwitch::Burning,
+                paused: state,
+                by: caller,
+            });
+            Ok(())
+        }
+
+        /// Whether a pause switch is on
+        #[ink(message)]
+        pub fn is_switch_paused(&self, switch: PauseSwitch) -> bool {
+            match switch {
+                PauseSwitch::Transfers => self.is_paused(),
+                PauseSwitch::Minting => self.minting_paused.get_or_default(),
+                PauseSwitch::Burning => self.burning_paused.get_or_default(),
+            }
+        }
+
+        /// Who last paused or unpaused transfers, when and why
+        #[ink(message)]
+        pub fn pause_info(&self) -> Option<PauseInfo> {
+            self.pause_info.get()
+        }
+
+        /// Blacklist / Unblacklist (owner or grantee)
+        #[ink(message)]
+        pub fn set_blacklist(&mut self, account: AccountId, state: bool) -> Result<()> {
+            let caller = self.ensure_permitted(ink::selector_bytes!("set_blacklist"))?;
+            self.apply_blacklist(caller, account, state, 0);
+            Ok(())
+        }
+
+        /// Blacklist / Unblacklist with a reason code (owner or grantee)
+        #[ink(message)]
+        pub fn set_blacklist_with_reason(
+            &mut self,
+            account: AccountId,
+            state: bool,
+            reason: u32,
+        ) -> Result<()> {
+            let caller = self.ensure_permitted(ink::selector_bytes!("set_blacklist_with_reason"))?;
+            self.apply_blacklist(caller, account, state, reason);
+            Ok(())
+        }
+
+        fn apply_blacklist(
+            &mut self,
+            caller: AccountId,
+            account: AccountId,
+            state: bool,
+            reason: u32,
+        ) {
+            if state != self.is_blacklisted(account) {
+                let mut accounts = self.blacklisted_accounts.get_or_default();
+                if state {
+                    accounts.push(account);
+                } else {
+                    accounts.retain(|a| *a != account);
+                }
+                self.blacklisted_accounts.set(&accounts);
+            }
+            self.blacklist.insert(account, &state);
+            if state {
+                let info = BlacklistInfo {
+                    reason,
+                    admin: caller,
+                    since: self.env().block_timestamp(),
+                };
+                self.blacklist_info.insert(account, &info);
+            } else {
+                self.blacklist_info.remove(account);
+            }
+            self.env().emit_event(BlacklistUpdated {
+                account,
+                blacklisted: state,
+                reason,
+                admin: caller,
+            });
+        }
+
+        /// Whether an account is blacklisted
+        #[ink(message)]
+        pub fn is_blacklisted(&self, account: AccountId) -> bool {
+            self.blacklist.get(account).unwrap_or(false)
+        }
+
+        /// Page of currently blacklisted accounts, in the order they were added
+        #[ink(message)]
+        pub fn blacklisted_accounts(&self, offset: u32, limit: u32) -> Vec<AccountId> {
+            self.blacklisted_accounts
+                .get_or_default()
+                .into_iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .collect()
+        }
+
+        /// Number of currently blacklisted accounts
+        #[ink(message)]
+        pub fn blacklisted_count(&self) -> u32 {
+            self.blacklisted_accounts.get_or_default().len() as u32
+        }
+
+        /// Confiscate `amount` from a blacklisted account, moving it to `to`
+        /// without fees or other transfer restrictions on `from` (owner or grantee)
+        #[ink(message)]
+        pub fn seize(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            amount: TokenBalance,
+        ) -> Result<()> {
+            let caller = self.ensure_permitted(ink::selector_bytes!("seize"))?;
+            if !self.is_blacklisted(from) {
+                return Err(Error::NotBlacklisted);
+            }
+            if self.is_blacklisted(to) {
+                return Err(Error::RecipientBlacklisted);
+            }
+            self.debit(from, amount)?;
+            self.deliver(from, to, amount)?;
+            self.note_treasury_inflow(from, to, amount)?;
+            self.env().emit_event(Seized {
+                from,
+                to,
+                amount,
+                by: caller,
+            });
+            Ok(())
+        }
+
+        /// Reason, admin and time of an account's blacklisting
+        #[ink(message)]
+        pub fn blacklist_info(&self, account: AccountId) -> Option<BlacklistInfo> {
+            self.blacklist_info.get(account)
+        }
+
+        /// Whitelist / Unwhitelist an account for restricted mode (owner or grantee)
+        #[ink(message)]
+        pub fn set_whitelist(&mut self, account: AccountId, state: bool) -> Result<()> {
+            let caller = self.ensure_permitted(ink::selector_bytes!("set_whitelist"))?;
+            if state {
+                self.whitelist.insert(account, &());
+            } else {
+                self.whitelist.remove(account);
+            }
+            self.env().emit_event(WhitelistUpdated {
+                account,
+                whitelisted: state,
+                admin: caller,
+            });
+            Ok(())
+        }
+
+        /// Whether an account is whitelisted
+        #[ink(message)]
+        pub fn is_whitelisted(&self, account: AccountId) -> bool {
+            self.whitelist.contains(account)
+        }
+
+        /// Turn restricted mode on or off; while on, only whitelisted accounts
+        /// may send or receive (owner or grantee)
+        #[ink(message)]
+        pub fn set_restricted_mode(&mut self, enabled: bool) -> Result<()> {
+            let caller = self.ensure_permitted(ink::selector_bytes!("set_restricted_mode"))?;
+            self.restricted_mode.set(&enabled);
+            self.env().emit_event(RestrictedModeChanged {
+                enabled,
+                by: caller,
+            });
+            Ok(())
+        }
+
+        /// Whether restricted mode is on
+        #[ink(message)]
+        pub fn restricted_mode(&self) -> bool {
+            self.restricted_mode.get_or_default()
+        }
+
+        /// Set (or clear with `None`) the contract whose
+        /// `ComplianceHook::check_transfer` must approve every transfer
+        /// (owner or grantee)
+        #[ink(message)]
+        pub fn set_compliance_contract(&mut self, contract: Option<AccountId>) -> Result<()> {
+            let caller = self.ensure_permitted(ink::selector_bytes!("set_compliance_contract"))?;
+            self.compliance_contract.set(&contract);
+            self.env().emit_event(ComplianceContractChanged {
+                contract,
+                by: caller,
+            });
+            Ok(())
+        }
+
+        /// Contract consulted on every transfer
+        #[ink(message)]
+        pub fn compliance_contract(&self) -> Option<AccountId> {
+            self.compliance_contract.get().flatten()
+        }
+
+        /// Set (or clear with `None`) the KYC registry both parties of a
+        /// transfer above the KYC threshold must be verified in (owner or grantee)
+        #[ink(message)]
+        pub fn set_kyc_registry(&mut self, registry: Option<AccountId>) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_kyc_registry"))?;
+            self.kyc_registry.set(&registry);
+            Ok(())
+        }
+
+        /// KYC registry contract
+        #[ink(message)]
+        pub fn kyc_registry(&self) -> Option<AccountId> {
+            self.kyc_registry.get().flatten()
+        }
+
+        /// Set the largest transfer allowed without KYC (owner or grantee)
+        #[ink(message)]
+        pub fn set_kyc_threshold(&mut self, amount: TokenBalance) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_kyc_threshold"))?;
+            self.kyc_threshold.set(&amount);
+            Ok(())
+        }
+
+        /// Largest transfer allowed without KYC
+        #[ink(message)]
+        pub fn kyc_threshold(&self) -> TokenBalance {
+            self.kyc_threshold.get_or_default()
+        }
+
+        /// Batch transfers (the native fee is charged once per batch)
+        #[ink(message, payable)]
+        pub fn batch_transfer(
+            &mut self,
+            recipients: Vec<AccountId>,
+            amounts: Vec<TokenBalance>,
+        ) -> Result<()> {
+            if recipients.len() != amounts.len() {
+                return Err(Error::LengthMismatch);
+            }
+            self.collect_native_fee()?;
+            self.send_batch(recipients.into_iter().zip(amounts).collect())
+        }
+
+        /// Checked batch send from the caller. Sender checks run and its
+        /// balance is debited once for the batch total; only recipient checks
+        /// and deliveries are repeated per entry.
+        fn send_batch(&mut self, entries: Vec<(AccountId, TokenBalance)>) -> Result<()> {
+            let caller = self.env().caller();
+            let mut total: TokenBalance = 0;
+            let mut legs = Vec::with_capacity(entries.len());
+            for (to, amount) in entries {
+                if self.is_noop_transfer(&caller, &to, amount)? {
+                    continue;
+                }
+                self.check_leg(&caller, &to, amount)?;
+                self.check_cosign_threshold(&caller, amount)?;
+                total = total.checked_add(amount).ok_or(Error::Overflow)?;
+                legs.push((to, amount));
+            }
+            if legs.is_empty() {
+                return Ok(());
+            }
+            self.check_sender(&caller, total)?;
+
+            self.debit(caller, total)?;
+            self.record_spending(caller, total);
+            for (to, amount) in legs {
+                self.deliver_quoted(caller, to, amount)?;
+            }
+            Ok(())
+        }
+
+        /// Batch `transfer_from`, each entry spending the caller's allowance
+        /// on its own source account. All entries succeed or the whole batch
+        /// reverts; the native fee is charged once per batch.
+        #[ink(message, payable)]
+        pub fn batch_transfer_from(
+            &mut self,
+            froms: Vec<AccountId>,
+            tos: Vec<AccountId>,
+            amounts: Vec<TokenBalance>,
+        ) -> Result<()> {
+            if froms.len() != tos.len() || tos.len() != amounts.len() {
+                return Err(Error::LengthMismatch);
+            }
+            self.collect_native_fee()?;
+
+            for ((from, to), amount) in froms.into_iter().zip(tos).zip(amounts) {
+                self.transfer_with_allowance(from, to, amount)?;
+            }
+            Ok(())
+        }
+
+        /// Batch transfer from a packed payload of back-to-back entries, each a
+        /// 32-byte recipient followed by a SCALE compact-encoded amount
+        #[ink(message, payable)]
+        pub fn batch_transfer_packed(&mut self, payload: Vec<u8>) -> Result<()> {
+            self.collect_native_fee()?;
+            let mut entries = Vec::new();
+            let mut input = payload.as_slice();
+            while !input.is_empty() {
+                let Some((account, rest)) = input.split_first_chunk::<32>() else {
+                    return Err(Error::InvalidPayload);
+                };
+                input = rest;
+                let amount = <Compact<TokenBalance> as Decode>::decode(&mut input)
+                    .map_err(|_| Error::InvalidPayload)?
+                    .0;
+                entries.push((AccountId::from(*account), amount));
+            }
+            self.send_batch(entries)
+        }
+
+        /// Dry-run a `batch_transfer` from the caller: reports the entries that
+        /// would fail, the total amount and a rough weight class for chunking
+        #[ink(message)]
+        pub fn validate_batch(
+            &self,
+            recipients: Vec<AccountId>,
+            amounts: Vec<TokenBalance>,
+        ) -> Result<BatchValidation> {
+            if recipients.len() != amounts.len() {
+                return Err(Error::LengthMismatch);
+            }
+            let caller = self.env().caller();
+            let mut balance = self.spendable_balance(&caller);
+            let mut total: TokenBalance = 0;
+            let mut failures = Vec::new();
+            for (index, (to, amount)) in recipients.iter().zip(&amounts).enumerate() {
+                total = total.saturating_add(*amount);
+                let mut result = self.can_transfer(&caller, to, *amount);
+                if result.is_ok() && balance < *amount {
+                    result = Err(Error::InsufficientBalance);
+                }
+                match result {
+                    Ok(()) => balance = balance.saturating_sub(*amount),
+                    Err(error) => failures.push((index as u32, error)),
+                }
+            }
+            Ok(BatchValidation {
+                total,
+                failures,
+                weight_class: WeightClass::of(recipients.len()),
+            })
+        }
+
+        /// Configure the charity receiving a share of every transfer (owner or grantee)
+        #[ink(message)]
+        pub fn set_donation(&mut self, charity: Option<AccountId>, bps: u16) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_donation"))?;
+            if u128::from(bps) > BPS_DENOMINATOR {
+                return Err(Error::InvalidBps);
+            }
+            self.donation.set(&DonationConfig { charity, bps });
+            Ok(())
+        }
+
+        /// Current donation settings
+        #[ink(message)]
+        pub fn donation_config(&self) -> DonationConfig {
+            self.donation.get_or_default()
+        }
+
+        /// Opt the caller's transfers out of (or back into) donations
+        #[ink(message)]
+        pub fn set_donation_opt_out(&mut self, opt_out: bool) -> Result<()> {
+            let caller = self.env().caller();
+            self.donation_opt_out.insert(caller, &opt_out);
+            Ok(())
+        }
+
+        /// Whether an account's transfers are exempt from donations
+        #[ink(message)]
+        pub fn is_donation_opt_out(&self, account: AccountId) -> bool {
+            self.donation_opt_out.get(account).unwrap_or(false)
+        }
+
+        /// Total amount donated to charities so far
+        #[ink(message)]
+        pub fn total_donated(&self) -> TokenBalance {
+            self.total_donated.get_or_default()
+        }
+
+        /// Set the transfer fee routed to the treasury, in basis points (owner or grantee)
+        #[ink(message)]
+        pub fn set_fee_bps(&mut self, bps: u16) -> Result<()> {
+            let caller = self.ensure_permitted(ink::selector_bytes!("set_fee_bps"))?;
+            self.apply_action(caller, AdminAction::SetFeeBps(bps))
+        }
+
+        /// Current transfer fee in basis points
+        #[ink(message)]
+        pub fn fee_bps(&self) -> u16 {
+            self.fee_bps.get_or_default()
+        }
+
+        /// Set (or clear with `None`, disabling the fee) the account receiving
+        /// transfer fees. This contract's own address keeps them in the
+        /// contract-managed treasury, paid out with `treasury_transfer`
+        /// (owner or grantee)
+        #[ink(message)]
+        pub fn set_treasury(&mut self, treasury: Option<AccountId>) -> Result<()> {
+            let caller = self.ensure_permitted(ink::selector_bytes!("set_treasury"))?;
+            self.apply_action(caller, AdminAction::SetTreasury(treasury))
+        }
+
+        /// Account receiving transfer fees
+        #[ink(message)]
+        pub fn treasury(&self) -> Option<AccountId> {
+            self.treasury.get().flatten()
+        }
+
+        /// Exempt an account's incoming and outgoing transfers from the fee
+        /// (owner or grantee)
+        #[ink(message)]
+        pub fn set_fee_exempt(&mut self, account: AccountId, exempt: bool) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_fee_exempt"))?;
+            if exempt {
+                self.fee_exempt.insert(account, &());
+            } else {
+                self.fee_exempt.remove(account);
+            }
+            Ok(())
+        }
+
+        /// Whether an account is on the fee exempt list
+        #[ink(message)]
+        pub fn is_fee_exempt(&self, account: AccountId) -> bool {
+            self.fee_exempt.contains(account)
+        }
+
+        /// Set the share of every transfer burned instead of delivered, in
+        /// basis points; zero disables burn-on-transfer (owner or grantee)
+        #[ink(message)]
+        pub fn set_burn_rate_bps(&mut self, bps: u16) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_burn_rate_bps"))?;
+            if u128::from(bps) > BPS_DENOMINATOR {
+                return Err(Error::InvalidBps);
+            }
+            self.burn_rate_bps.set(&bps);
+            Ok(())
+        }
+
+        /// Current burn-on-transfer rate in basis points
+        #[ink(message)]
+        pub fn burn_rate_bps(&self) -> u16 {
+            self.burn_rate_bps.get_or_default()
+        }
+
+        /// Add the transferred native value to the redemption reserve (owner or grantee)
+        #[ink(message, payable)]
+        pub fn fund_redemption_reserve(&mut self) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("fund_redemption_reserve"))?;
+            let mut config = self.redemption.get_or_default();
+            config.reserve = config
+                .reserve
+                .checked_add(self.env().transferred_value())
+                .ok_or(Error::Overflow)?;
+            self.redemption.set(&config);
+            Ok(())
+        }
+
+        /// Take native currency back out of the redemption reserve (owner or grantee)
+        #[ink(message)]
+        pub fn withdraw_redemption_reserve(&mut self, amount: Balance) -> Result<()> {
+            let caller =
+                self.ensure_permitted(ink::selector_bytes!("withdraw_redemption_reserve"))?;
+            let mut config = self.redemption.get_or_default();
+            if config.reserve < amount {
+                return Err(Error::InsufficientReserve);
+            }
+            config.reserve = config.reserve.saturating_sub(amount);
+            self.redemption.set(&config);
+            self.env()
+                .transfer(caller, amount)
+                .map_err(|_| Error::NativeTransferFailed)
+        }
+
+        /// Set the redemption rate, zero disables redemption (owner or grantee)
+        #[ink(message)]
+        pub fn set_redemption_rate(&mut self, rate: u128) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_redemption_rate"))?;
+            let mut config = self.redemption.get_or_default();
+            config.rate = rate;
+            self.redemption.set(&config);
+            Ok(())
+        }
+
+        /// Pause / Unpause redemptions (owner or grantee)
+        #[ink(message)]
+        pub fn set_redemption_paused(&mut self, state: bool) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_redemption_paused"))?;
+            let mut config = self.redemption.get_or_default();
+            config.paused = state;
+            self.redemption.set(&config);
+            Ok(())
+        }
+
+        /// Current redemption settings and reserve
+        #[ink(message)]
+        pub fn redemption_config(&self) -> RedemptionConfig {
+            self.redemption.get_or_default()
+        }
+
+        /// Native amount paid for redeeming `amount` tokens at the current rate
+        #[ink(message)]
+        pub fn redemption_quote(&self, amount: TokenBalance) -> Result<Balance> {
+            let rate = self.redemption.get_or_default().rate;
+            mul_div(amount, rate, REDEMPTION_RATE_SCALE).ok_or(Error::Overflow)
+        }
+
+        /// Burn own tokens in exchange for native currency from the reserve
+        #[ink(message)]
+        pub fn redeem(&mut self, amount: TokenBalance) -> Result<Balance> {
+            let caller = self.env().caller();
+            let mut config = self.redemption.get_or_default();
+            if config.rate == 0 {
+                return Err(Error::RedemptionDisabled);
+            }
+            if config.paused {
+                return Err(Error::RedemptionPaused);
+            }
+            let native_amount = self.redemption_quote(amount)?;
+            if config.reserve < native_amount {
+                return Err(Error::InsufficientReserve);
+            }
+
+            self.burn_internal(caller, amount)?;
+            config.reserve = config.reserve.saturating_sub(native_amount);
+            self.redemption.set(&config);
+            self.env()
+                .transfer(caller, native_amount)
+                .map_err(|_| Error::NativeTransferFailed)?;
+
+            self.env().emit_event(Redeemed {
+                account: caller,
+                amount,
+                native_amount,
+            });
+            Ok(native_amount)
+        }
+
+        /// Register or update a PSP34 collection whose holders get a reward
+        /// multiplier, in basis points (owner or grantee)
+        #[ink(message)]
+        pub fn set_boost_collection(
+            &mut self,
+            collection: AccountId,
+            factor_bps: u32,
+        ) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_boost_collection"))?;
+            if factor_bps < NO_BOOST_BPS {
+                return Err(Error::InvalidBoostFactor);
+            }
+            let mut collections = self.boost_collections.get_or_default();
+            match collections.iter_mut().find(|(c, _)| *c == collection) {
+                Some(entry) => entry.1 = factor_bps,
+                None => {
+                    if collections.len() >= MAX_BOOST_COLLECTIONS {
+                        return Err(Error::TooManyBoostCollections);
+                    }
+                    collections.push((collection, factor_bps));
+                }
+            }
+            self.boost_collections.set(&collections);
+            self.env().emit_event(BoostCollectionUpdated {
+                collection,
+                factor_bps: Some(factor_bps),
+            });
+            Ok(())
+        }
+
+        /// Remove a boost collection (owner or grantee)
+        #[ink(message)]
+        pub fn remove_boost_collection(&mut self, collection: AccountId) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("remove_boost_collection"))?;
+            let mut collections = self.boost_collections.get_or_default();
+            collections.retain(|(c, _)| *c != collection);
+            self.boost_collections.set(&collections);
+            self.env().emit_event(BoostCollectionUpdated {
+                collection,
+                factor_bps: None,
+            });
+            Ok(())
+        }
+
+        /// Registered boost collections and their factors
+        #[ink(message)]
+        pub fn boost_collections(&self) -> Vec<(AccountId, u32)> {
+            self.boost_collections.get_or_default()
+        }
+
+        /// Reward multiplier of an account in basis points: the highest factor
+        /// among the boost collections it currently holds a token of. Ownership
+        /// is checked live via `PSP34::balance_of`; a failing call counts as
+        /// not holding.
+        #[ink(message)]
+        pub fn reward_boost_of(&self, account: AccountId) -> u32 {
+            self.boost_collections
+                .get_or_default()
+                .into_iter()
+                .filter(|(collection, _)| Self::holds_nft(*collection, account))
+                .map(|(_, factor_bps)| factor_bps)
+                .fold(NO_BOOST_BPS, u32::max)
+        }
+
+        /// Whether `account` owns at least one token of a PSP34 collection
+        fn holds_nft(collection: AccountId, account: AccountId) -> bool {
+            build_call::<Environment>()
+                .call(collection)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(PSP34_BALANCE_OF)).push_arg(account),
+                )
+                .returns::<u32>()
+                .try_invoke()
+                .map(|result| matches!(result, Ok(balance) if balance > 0))
+                .unwrap_or(false)
+        }
+
+        /// Create or update a partner program's reward multiplier, in basis
+        /// points (owner or grantee)
+        #[ink(message)]
+        pub fn set_partner_program(
+            &mut self,
+            program_id: u32,
+            multiplier_bps: u32,
+        ) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_partner_program"))?;
+            if multiplier_bps < NO_BOOST_BPS {
+                return Err(Error::InvalidBoostFactor);
+            }
+            self.partner_programs.insert(program_id, &multiplier_bps);
+            self.env().emit_event(PartnerProgramUpdated {
+                program_id,
+                multiplier_bps: Some(multiplier_bps),
+            });
+            Ok(())
+        }
+
+        /// Close a partner program; enrolled accounts fall back to no
+        /// multiplier (owner or grantee)
+        #[ink(message)]
+        pub fn remove_partner_program(&mut self, program_id: u32) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("remove_partner_program"))?;
+            self.partner_programs.remove(program_id);
+            self.env().emit_event(PartnerProgramUpdated {
+                program_id,
+                multiplier_bps: None,
+            });
+            Ok(())
+        }
+
+        /// Multiplier of a partner program, if it exists
+        #[ink(message)]
+        pub fn partner_program(&self, program_id: u32) -> Option<u32> {
+            self.partner_programs.get(program_id)
+        }
+
+        /// Enroll the caller in a partner program, or leave with `None`
+        #[ink(message)]
+        pub fn enroll_partner(&mut self, program_id: Option<u32>) -> Result<()> {
+            let caller = self.env().caller();
+            match program_id {
+                Some(id) => {
+                    if !self.partner_programs.contains(id) {
+                        return Err(Error::UnknownPartnerProgram);
+                    }
+                    self.partner_enrollment.insert(caller, &id);
+                }
+                None => self.partner_enrollment.remove(caller),
+            }
+            self.env().emit_event(PartnerEnrolled {
+                account: caller,
+                program_id,
+            });
+            Ok(())
+        }
+
+        /// Partner program an account is enrolled in
+        #[ink(message)]
+        pub fn partner_of(&self, account: AccountId) -> Option<u32> {
+            self.partner_enrollment.get(account)
+        }
+
+        /// Reward multiplier an account gets from its partner program, in basis points
+        #[ink(message)]
+        pub fn partner_multiplier_of(&self, account: AccountId) -> u32 {
+            self.partner_enrollment
+                .get(account)
+                .and_then(|id| self.partner_programs.get(id))
+                .unwrap_or(NO_BOOST_BPS)
+        }
+
+        /// Open the swap from a predecessor token (owner or grantee). Reconfiguring
+        /// keeps the running totals.
+        #[ink(message)]
+        pub fn configure_token_migration(
+            &mut self,
+            old_token: AccountId,
+            ratio_numerator: u128,
+            ratio_denominator: u128,
+            deadline: Timestamp,
+            burn_old: bool,
+        ) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("configure_token_migration"))?;
+            if ratio_numerator == 0 || ratio_denominator == 0 {
+                return Err(Error::InvalidMigrationConfig);
+            }
+            let (total_old, total_new, swaps) = self
+                .token_migration
+                .get()
+                .map(|m| (m.total_old, m.total_new, m.swaps))
+                .unwrap_or_default();
+            self.token_migration.set(&TokenMigration {
+                old_token,
+                ratio_numerator,
+                ratio_denominator,
+                deadline,
+                burn_old,
+                total_old,
+                total_new,
+                swaps,
+            });
+            Ok(())
+        }
+
+        /// Token migration settings and stats
+        #[ink(message)]
+        pub fn token_migration(&self) -> Option<TokenMigration> {
+            self.token_migration.get()
+        }
+
+        /// Swap `amount` of the predecessor token for new tokens. The old tokens
+        /// are pulled from the caller (who must have approved this contract) and
+        /// either locked here or burned.
+        #[ink(message)]
+        pub fn migrate_tokens(&mut self, amount: TokenBalance) -> Result<TokenBalance> {
+            let caller = self.env().caller();
+            let mut migration = self
+                .token_migration
+                .get()
+                .ok_or(Error::MigrationNotConfigured)?;
+            if self.env().block_timestamp() > migration.deadline {
+                return Err(Error::MigrationClosed);
+            }
+            let new_amount =
+                mul_div(amount, migration.ratio_numerator, migration.ratio_denominator)
+                    .ok_or(Error::Overflow)?;
+
+            let this = self.env().account_id();
+            let pulled = build_call::<Environment>()
+                .call(migration.old_token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(PSP22_TRANSFER_FROM))
+                        .push_arg(caller)
+                        .push_arg(this)
+                        .push_arg(amount)
+                        .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<core::result::Result<(), PSP22Error>>()
+                .try_invoke();
+            if !matches!(pulled, Ok(Ok(Ok(())))) {
+                return Err(Error::OldTokenCallFailed);
+            }
+            if migration.burn_old {
+                let burned = build_call::<Environment>()
+                    .call(migration.old_token)
+                    .exec_input(ExecutionInput::new(Selector::new(PSP22_BURN)).push_arg(amount))
+                    .returns::<core::result::Result<(), PSP22Error>>()
+                    .try_invoke();
+                if !matches!(burned, Ok(Ok(Ok(())))) {
+                    return Err(Error::OldTokenCallFailed);
+                }
+            }
+
+            self.mint_internal(caller, new_amount, None)?;
+            migration.total_old = migration.total_old.saturating_add(amount);
+            migration.total_new = migration.total_new.saturating_add(new_amount);
+            migration.swaps = migration.swaps.saturating_add(1);
+            self.token_migration.set(&migration);
+            self.env().emit_event(TokensMigrated {
+                account: caller,
+                old_amount: amount,
+                new_amount,
+            });
+            Ok(new_amount)
+        }
+
+        /// Take a balance snapshot (owner or grantee), returns its id
+        #[ink(message)]
+        pub fn snapshot(&mut self) -> Result<u32> {
+            self.ensure_permitted(ink::selector_bytes!("snapshot"))?;
+            let id = self
+                .snapshot_id
+                .get_or_default()
+                .checked_add(1)
+                .ok_or(Error::Overflow)?;
+            self.snapshot_id.set(&id);
+            self.env().emit_event(Snapshot { id });
+            Ok(id)
+        }
+
+        /// Latest snapshot id, 0 if none was taken
+        #[ink(message)]
+        pub fn current_snapshot_id(&self) -> u32 {
+            self.snapshot_id.get_or_default()
+        }
+
+        /// Balance of an account at the time a snapshot was taken
+        #[ink(message)]
+        pub fn balance_of_at(&self, account: AccountId, snapshot_id: u32) -> Result<TokenBalance> {
+            if snapshot_id == 0 || snapshot_id > self.snapshot_id.get_or_default() {
+                return Err(Error::InvalidSnapshot);
+            }
+            // the first change recorded at or after the snapshot holds the
+            // balance as it was at the snapshot; no change means it is unchanged
+            let snapshots = self.balance_snapshots.get(account).unwrap_or_default();
+            let index = snapshots.partition_point(|(id, _)| *id < snapshot_id);
+            Ok(match snapshots.get(index) {
+                Some((_, balance)) => *balance,
+                None => self.balance_of(account),
+            })
+        }
+
+        /// Create an airdrop paying `ratio_bps` of each account's balance at
+        /// `snapshot_id` (owner or grantee), returns the airdrop id
+        #[ink(message)]
+        pub fn create_snapshot_airdrop(
+            &mut self,
+            snapshot_id: u32,
+            ratio_bps: u32,
+        ) -> Result<u32> {
+            self.ensure_permitted(ink::selector_bytes!("create_snapshot_airdrop"))?;
+            if snapshot_id == 0 || snapshot_id > self.snapshot_id.get_or_default() {
+                return Err(Error::InvalidSnapshot);
+            }
+            let airdrop_id = self.snapshot_airdrop_count.get_or_default();
+            self.snapshot_airdrops.insert(
+                airdrop_id,
+                &SnapshotAirdrop {
+                    snapshot_id,
+                    ratio_bps,
+                    total_claimed: 0,
+                },
+            );
+            let next_id = airdrop_id.checked_add(1).ok_or(Error::Overflow)?;
+            self.snapshot_airdrop_count.set(&next_id);
+            self.env().emit_event(SnapshotAirdropCreated {
+                airdrop_id,
+                snapshot_id,
+                ratio_bps,
+            });
+            Ok(airdrop_id)
+        }
+
+        /// Snapshot airdrop details
+        #[ink(message)]
+        pub fn snapshot_airdrop(&self, airdrop_id: u32) -> Option<SnapshotAirdrop> {
+            self.snapshot_airdrops.get(airdrop_id)
+        }
+
+        /// Amount an account can still claim from a snapshot airdrop
+        #[ink(message)]
+        pub fn snapshot_airdrop_entitlement(
+            &self,
+            airdrop_id: u32,
+            account: AccountId,
+        ) -> Result<TokenBalance> {
+            let airdrop = self
+                .snapshot_airdrops
+                .get(airdrop_id)
+                .ok_or(Error::UnknownAirdrop)?;
+            if self.snapshot_airdrop_claimed.contains((airdrop_id, account)) {
+                return Ok(0);
+            }
+            let balance = self.balance_of_at(account, airdrop.snapshot_id)?;
+            mul_div(balance, u128::from(airdrop.ratio_bps), BPS_DENOMINATOR).ok_or(Error::Overflow)
+        }
+
+        /// Mint the caller's entitlement from a snapshot airdrop
+        #[ink(message)]
+        pub fn claim_snapshot_airdrop(&mut self, airdrop_id: u32) -> Result<TokenBalance> {
+            let caller = self.env().caller();
+            if self.snapshot_airdrop_claimed.contains((airdrop_id, caller)) {
+                return Err(Error::AlreadyClaimed);
+            }
+            let amount = self.snapshot_airdrop_entitlement(airdrop_id, caller)?;
+            if amount == 0 {
+                return Err(Error::NothingToClaim);
+            }
+            let mut airdrop = self
+                .snapshot_airdrops
+                .get(airdrop_id)
+                .ok_or(Error::UnknownAirdrop)?;
+            airdrop.total_claimed = airdrop.total_claimed.saturating_add(amount);
+            self.snapshot_airdrops.insert(airdrop_id, &airdrop);
+            self.snapshot_airdrop_claimed.insert((airdrop_id, caller), &());
+            self.mint_internal(caller, amount, None)?;
+            self.env().emit_event(SnapshotAirdropClaimed {
+                airdrop_id,
+                account: caller,
+                amount,
+            });
+            Ok(amount)
+        }
+
+        /// Attach or update a named document (owner or grantee)
+        #[ink(message)]
+        pub fn set_document(
+            &mut self,
+            name: [u8; 32],
+            uri: String,
+            content_hash: Hash,
+        ) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_document"))?;
+            if uri.len() > MAX_DOCUMENT_URI_LEN {
+                return Err(Error::UriTooLong);
+            }
+            if !self.documents.contains(name) {
+                let mut names = self.document_names.get_or_default();
+                names.push(name);
+                self.document_names.set(&names);
+            }
+            let document = Document {
+                uri: uri.clone(),
+                content_hash,
+                updated_at: self.env().block_timestamp(),
+            };
+            self.documents.insert(name, &document);
+            self.env().emit_event(DocumentUpdated {
+                name,
+                uri,
+                content_hash,
+            });
+            Ok(())
+        }
+
+        /// Remove a named document (owner or grantee)
+        #[ink(message)]
+        pub fn remove_document(&mut self, name: [u8; 32]) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("remove_document"))?;
+            if self.documents.take(name).is_none() {
+                return Err(Error::UnknownDocument);
+            }
+            let mut names = self.document_names.get_or_default();
+            names.retain(|n| *n != name);
+            self.document_names.set(&names);
+            self.env().emit_event(DocumentRemoved { name });
+            Ok(())
+        }
+
+        /// A named document
+        #[ink(message)]
+        pub fn get_document(&self, name: [u8; 32]) -> Option<Document> {
+            self.documents.get(name)
+        }
+
+        /// Names of all attached documents
+        #[ink(message)]
+        pub fn document_names(&self) -> Vec<[u8; 32]> {
+            self.document_names.get_or_default()
+        }
+
+        /// Earmark `amount` of the caller's free balance under `label`
+        #[ink(message)]
+        pub fn allocate_sub_balance(
+            &mut self,
+            label: [u8; 32],
+            amount: TokenBalance,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            let free = self
+                .balance_of(caller)
+                .saturating_sub(self.allocated_balance(&caller));
+            if free < amount {
+                return Err(Error::InsufficientBalance);
+            }
+            self.adjust_sub_balance(caller, label, amount, true)
+        }
+
+        /// Return `amount` from the sub-account `label` to the free balance
+        #[ink(message)]
+        pub fn release_sub_balance(&mut self, label: [u8; 32], amount: TokenBalance) -> Result<()> {
+            let caller = self.env().caller();
+            self.adjust_sub_balance(caller, label, amount, false)
+        }
+
+        /// Move `amount` between two of the caller's sub-accounts
+        #[ink(message)]
+        pub fn move_sub_balance(
+            &mut self,
+            from_label: [u8; 32],
+            to_label: [u8; 32],
+            amount: TokenBalance,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            self.adjust_sub_balance(caller, from_label, amount, false)?;
+            self.adjust_sub_balance(caller, to_label, amount, true)
+        }
+
+        /// Transfer out of a sub-account: releases `amount` from `label` and
+        /// sends it to `to`
+        #[ink(message, payable)]
+        pub fn transfer_from_sub_account(
+            &mut self,
+            label: [u8; 32],
+            to: AccountId,
+            amount: TokenBalance,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            self.adjust_sub_balance(caller, label, amount, false)?;
+            self.transfer(to, amount)
+        }
+
+        /// When restricted, transfers and burns can only draw on the caller's
+        /// free balance and never eat into sub-accounts
+        #[ink(message)]
+        pub fn set_sub_accounts_restricted(&mut self, restricted: bool) -> Result<()> {
+            let caller = self.env().caller();
+            self.sub_accounts_restricted.insert(caller, &restricted);
+            Ok(())
+        }
+
+        /// Whether an account's sub-accounts are shielded from transfers
+        #[ink(message)]
+        pub fn sub_accounts_restricted(&self, account: AccountId) -> bool {
+            self.sub_accounts_restricted.get(account).unwrap_or(false)
+        }
+
+        /// Balance earmarked under `label`
+        #[ink(message)]
+        pub fn sub_balance_of(&self, account: AccountId, label: [u8; 32]) -> TokenBalance {
+            self.sub_accounts
+                .get(account)
+                .unwrap_or_default()
+                .into_iter()
+                .find(|(l, _)| *l == label)
+                .map(|(_, amount)| amount)
+                .unwrap_or(0)
+        }
+
+        /// All non-empty sub-accounts of an account
+        #[ink(message)]
+        pub fn sub_accounts_of(&self, account: AccountId) -> SubAccounts {
+            self.sub_accounts.get(account).unwrap_or_default()
+        }
+
+        /// Add to (`increase`) or take from a sub-account
+        fn adjust_sub_balance(
+            &mut self,
+            account: AccountId,
+            label: [u8; 32],
+            amount: TokenBalance,
+            increase: bool,
+        ) -> Result<()> {
+            let mut buckets = self.sub_accounts.get(account).unwrap_or_default();
+            let index = match buckets.iter().position(|(l, _)| *l == label) {
+                Some(index) => index,
+                None if !increase => return Err(Error::InsufficientSubBalance),
+                None => {
+                    if buckets.len() >= MAX_SUB_ACCOUNTS {
+                        return Err(Error::TooManySubAccounts);
+                    }
+                    buckets.push((label, 0));
+                    buckets.len() - 1
+                }
+            };
+            let bucket = &mut buckets[index].1;
+            if increase {
+                *bucket = bucket.checked_add(amount).ok_or(Error::Overflow)?;
+            } else {
+                *bucket = bucket.checked_sub(amount).ok_or(Error::InsufficientSubBalance)?;
+            }
+            let balance = *bucket;
+            buckets.retain(|(_, bucket)| *bucket > 0);
+            self.sub_accounts.insert(account, &buckets);
+            self.env().emit_event(SubBalanceChanged {
+                account,
+                label,
+                balance,
+            });
+            Ok(())
+        }
+
+        /// Current owner
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+
+        /// Configure (or clear with `None`) the backup account that may claim
+        /// ownership after `timeout` ms without a ping (owner only)
+        #[ink(message)]
+        pub fn set_dead_man_switch(
+            &mut self,
+            backup: Option<AccountId>,
+            timeout: Timestamp,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            if caller != self.owner {
+                return Err(Error::NotOwner);
+            }
+            if backup.is_some() && timeout < MIN_DEAD_MAN_TIMEOUT {
+                return Err(Error::InvalidTimeout);
+            }
+            let switch = backup.map(|backup| DeadManSwitch {
+                backup,
+                timeout,
+                last_ping: self.env().block_timestamp(),
+            });
+            self.dead_man_switch.set(&switch);
+            Ok(())
+        }
+
+        /// Dead-man switch settings
+        #[ink(message)]
+        pub fn dead_man_switch(&self) -> Option<DeadManSwitch> {
+            self.dead_man_switch.get().flatten()
+        }
+
+        /// Owner heartbeat, resets the dead-man switch timer (owner only)
+        #[ink(message)]
+        pub fn ping(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            if caller != self.owner {
+                return Err(Error::NotOwner);
+            }
+            let mut switch = self.dead_man_switch().ok_or(Error::DeadManSwitchNotSet)?;
+            switch.last_ping = self.env().block_timestamp();
+            self.dead_man_switch.set(&Some(switch));
+            Ok(())
+        }
+
+        /// Take over ownership after the owner missed the heartbeat timeout
+        /// (backup only). The switch is disarmed afterwards.
+        #[ink(message)]
+        pub fn claim_ownership(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            let switch = self.dead_man_switch().ok_or(Error::DeadManSwitchNotSet)?;
+            if caller != switch.backup {
+                return Err(Error::NotBackup);
+            }
+            let deadline = switch.last_ping.saturating_add(switch.timeout);
+            if self.env().block_timestamp() <= deadline {
+                return Err(Error::OwnerStillActive);
+            }
+            let previous_owner = self.owner;
+            self.owner = caller;
+            self.dead_man_switch.set(&None);
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner,
+                new_owner: caller,
+            });
+            Ok(())
+        }
+
+        /// Designate (or remove with `None`) a beneficiary who may claim the
+        /// caller's balance after `inactivity_period` ms without activity
+        #[ink(message)]
+        pub fn set_beneficiary(
+            &mut self,
+            beneficiary: Option<AccountId>,
+            inactivity_period: Timestamp,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            match beneficiary {
+                Some(beneficiary) => {
+                    if inactivity_period < MIN_INACTIVITY_PERIOD {
+                        return Err(Error::InvalidTimeout);
+                    }
+                    let plan = Inheritance {
+                        beneficiary,
+                        inactivity_period,
+                        last_active: self.env().block_timestamp(),
+                        claim_started: None,
+                    };
+                    self.inheritance.insert(caller, &plan);
+                }
+                None => self.inheritance.remove(caller),
+            }
+            self.env().emit_event(BeneficiarySet {
+                account: caller,
+                beneficiary,
+            });
+            Ok(())
+        }
+
+        /// Estate recovery plan of an account
+        #[ink(message)]
+        pub fn inheritance_of(&self, account: AccountId) -> Option<Inheritance> {
+            self.inheritance.get(account)
+        }
+
+        /// Start a claim on an inactive account's balance (beneficiary only)
+        #[ink(message)]
+        pub fn start_inheritance_claim(&mut self, account: AccountId) -> Result<()> {
+            let caller = self.env().caller();
+            let mut plan = self.inheritance.get(account).ok_or(Error::NoBeneficiary)?;
+            if caller != plan.beneficiary {
+                return Err(Error::NotBeneficiary);
+            }
+            let now = self.env().block_timestamp();
+            if now <= plan.last_active.saturating_add(plan.inactivity_period) {
+                return Err(Error::AccountStillActive);
+            }
+            plan.claim_started = Some(now);
+            self.inheritance.insert(account, &plan);
+            self.env().emit_event(InheritanceClaimStarted {
+                account,
+                beneficiary: caller,
+            });
+            Ok(())
+        }
+
+        /// Cancel a pending claim on the caller's balance; also counts as activity
+        #[ink(message)]
+        pub fn cancel_inheritance_claim(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            let mut plan = self.inheritance.get(caller).ok_or(Error::NoBeneficiary)?;
+            if plan.claim_started.is_none() {
+                return Err(Error::NoPendingClaim);
+            }
+            plan.claim_started = None;
+            plan.last_active = self.env().block_timestamp();
+            self.inheritance.insert(caller, &plan);
+            self.env().emit_event(InheritanceClaimCancelled { account: caller });
+            Ok(())
+        }
+
+        /// Move the whole balance of an inactive account to its beneficiary
+        /// once the challenge period of a pending claim has passed (beneficiary only)
+        #[ink(message)]
+        pub fn finalize_inheritance_claim(&mut self, account: AccountId) -> Result<TokenBalance> {
+            let caller = self.env().caller();
+            let plan = self.inheritance.get(account).ok_or(Error::NoBeneficiary)?;
+            if caller != plan.beneficiary {
+                return Err(Error::NotBeneficiary);
+            }
+            let started = plan.claim_started.ok_or(Error::NoPendingClaim)?;
+            if plan.last_active > started {
+                return Err(Error::AccountStillActive);
+            }
+            let challenge_end = started.saturating_add(INHERITANCE_CHALLENGE_PERIOD);
+            if self.env().block_timestamp() <= challenge_end {
+                return Err(Error::ChallengePeriodActive);
+            }
+            // the holder's self-imposed spending limits do not apply to inheritance
+            self.can_transfer(&account, &caller, 0)?;
+            let amount = self.balance_of(account);
+            self.inheritance.remove(account);
+            self.sub_accounts.remove(account);
+            self.transfer_internal(account, caller, amount)?;
+            self.env().emit_event(InheritanceClaimed {
+                account,
+                beneficiary: caller,
+                amount,
+            });
+            Ok(amount)
+        }
+
+        /// Enable balance decay, or disable it with `None` (owner or grantee).
+        /// Accounts start decaying from their next balance change.
+        #[ink(message)]
+        pub fn set_demurrage(&mut self, config: Option<Demurrage>) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_demurrage"))?;
+            if let Some(config) = &config {
+                if u128::from(config.rate_bps) > BPS_DENOMINATOR {
+                    return Err(Error::InvalidBps);
+                }
+                if config.period == 0 {
+                    return Err(Error::InvalidTimeout);
+                }
+            }
+            self.demurrage.set(&config);
+            Ok(())
+        }
+
+        /// Balance decay settings
+        #[ink(message)]
+        pub fn demurrage(&self) -> Option<Demurrage> {
+            self.demurrage.get().flatten()
+        }
+
+        /// Apply accrued decay to an account now, so its stored balance and the
+        /// total supply catch up (callable by anyone)
+        #[ink(message)]
+        pub fn settle(&mut self, account: AccountId) -> Result<()> {
+            self.settle_demurrage(account)?;
+            Ok(())
+        }
+
+        /// Set the caller's own outbound limits. Stricter limits apply at once;
+        /// looser ones (or `None` to remove them) only after the current
+        /// unlock delay.
+        #[ink(message)]
+        pub fn set_spending_limit(&mut self, limit: Option<SpendingLimit>) -> Result<()> {
+            let caller = self.env().caller();
+            let now = self.env().block_timestamp();
+            let current = self.spending_limit_of(caller);
+            let delay = match (&current, &limit) {
+                (None, _) => 0,
+                (Some(current), Some(new)) if !current.is_loosened_by(new) => 0,
+                (Some(current), _) => current.unlock_delay,
+            };
+            let effective_at = now.saturating_add(delay);
+            if let Some(current) = &current {
+                // settle the limit in force before queueing a new one
+                self.spending_limits.insert(caller, current);
+            }
+            match (&limit, delay) {
+                (Some(new), 0) => {
+                    self.spending_limits.insert(caller, new);
+                    self.pending_spending_limits.remove(caller);
+                }
+                (None, 0) => {
+                    self.spending_limits.remove(caller);
+                    self.pending_spending_limits.remove(caller);
+                }
+                (Some(new), _) => {
+                    self.pending_spending_limits.insert(caller, &(new.clone(), effective_at));
+                }
+                (None, _) => {
+                    let unlimited = SpendingLimit {
+                        max_per_transfer: TokenBalance::MAX,
+                        max_per_day: TokenBalance::MAX,
+                        unlock_delay: 0,
+                    };
+                    self.pending_spending_limits.insert(caller, &(unlimited, effective_at));
+                }
+            }
+            self.env().emit_event(SpendingLimitChanged {
+                account: caller,
+                limit,
+                effective_at,
+            });
+            Ok(())
+        }
+
+        /// Outbound limit currently in force for an account
+        #[ink(message)]
+        pub fn spending_limit_of(&self, account: AccountId) -> Option<SpendingLimit> {
+            if let Some((limit, effective_at)) = self.pending_spending_limits.get(account) {
+                if effective_at <= self.env().block_timestamp() {
+                    return Some(limit);
+                }
+            }
+            self.spending_limits.get(account)
+        }
+
+        /// Looser limit waiting for its unlock delay, and when it takes effect
+        #[ink(message)]
+        pub fn pending_spending_limit_of(
+            &self,
+            account: AccountId,
+        ) -> Option<(SpendingLimit, Timestamp)> {
+            self.pending_spending_limits
+                .get(account)
+                .filter(|(_, effective_at)| *effective_at > self.env().block_timestamp())
+        }
+
+        /// Amount an account has sent today (UTC)
+        #[ink(message)]
+        pub fn spent_today(&self, account: AccountId) -> TokenBalance {
+            match self.spending_usage.get(account) {
+                Some((day, spent)) if day == self.env().block_timestamp() / DAY => spent,
+                _ => 0,
+            }
+        }
+
+        /// Cap how much new allowance the caller can grant per day. Tighter
+        /// limits apply at once; looser ones (or opting out with `None`) only
+        /// after the current unlock delay.
+        #[ink(message)]
+        pub fn set_approval_limit(&mut self, limit: Option<ApprovalLimit>) -> Result<()> {
+            let caller = self.env().caller();
+            let now = self.env().block_timestamp();
+            let current = self.approval_limit_of(caller);
+            let delay = match (&current, &limit) {
+                (None, _) => 0,
+                (Some(current), Some(new)) if !current.is_loosened_by(new) => 0,
+                (Some(current), _) => current.unlock_delay,
+            };
+            let effective_at = now.saturating_add(delay);
+            if let Some(current) = &current {
+                // settle the limit in force before queueing a new one
+                self.approval_limits.insert(caller, current);
+            }
+            match (&limit, delay) {
+                (Some(new), 0) => {
+                    self.approval_limits.insert(caller, new);
+                    self.pending_approval_limits.remove(caller);
+                }
+                (None, 0) => {
+                    self.approval_limits.remove(caller);
+                    self.pending_approval_limits.remove(caller);
+                }
+                (Some(new), _) => {
+                    self.pending_approval_limits.insert(caller, &(new.clone(), effective_at));
+                }
+                (None, _) => {
+                    let unlimited = ApprovalLimit {
+                        max_per_day: TokenBalance::MAX,
+                        unlock_delay: 0,
+                    };
+                    self.pending_approval_limits.insert(caller, &(unlimited, effective_at));
+                }
+            }
+            self.env().emit_event(ApprovalLimitChanged {
+                account: caller,
+                limit,
+                effective_at,
+            });
+            Ok(())
+        }
+
+        /// Approval limit currently in force for an account
+        #[ink(message)]
+        pub fn approval_limit_of(&self, account: AccountId) -> Option<ApprovalLimit> {
+            if let Some((limit, effective_at)) = self.pending_approval_limits.get(account) {
+                if effective_at <= self.env().block_timestamp() {
+                    return Some(limit);
+                }
+            }
+            self.approval_limits.get(account)
+        }
+
+        /// Looser approval limit waiting for its unlock delay, and when it
+        /// takes effect
+        #[ink(message)]
+        pub fn pending_approval_limit_of(
+            &self,
+            account: AccountId,
+        ) -> Option<(ApprovalLimit, Timestamp)> {
+            self.pending_approval_limits
+                .get(account)
+                .filter(|(_, effective_at)| *effective_at > self.env().block_timestamp())
+        }
+
+        /// New allowance an account has granted today (UTC)
+        #[ink(message)]
+        pub fn approved_today(&self, account: AccountId) -> TokenBalance {
+            match self.approval_usage.get(account) {
+                Some((day, approved)) if day == self.env().block_timestamp() / DAY => approved,
+                _ => 0,
+            }
+        }
+
+        /// Record or replace an account's attestation (compliance role only)
+        #[ink(message)]
+        pub fn attest(
+            &mut self,
+            account: AccountId,
+            level: u8,
+            jurisdiction: u16,
+            expires_at: Timestamp,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            if !self.has_role(COMPLIANCE_ROLE, caller) {
+                return Err(Error::NotComplianceOfficer);
+            }
+            let attestation = Attestation {
+                level,
+                jurisdiction,
+                expires_at,
+                attestor: caller,
+            };
+            self.attestations.insert(account, &attestation);
+            self.env().emit_event(AttestationUpdated {
+                account,
+                attestation: Some(attestation),
+            });
+            Ok(())
+        }
+
+        /// Remove an account's attestation (compliance role only)
+        #[ink(message)]
+        pub fn revoke_attestation(&mut self, account: AccountId) -> Result<()> {
+            let caller = self.env().caller();
+            if !self.has_role(COMPLIANCE_ROLE, caller) {
+                return Err(Error::NotComplianceOfficer);
+            }
+            self.attestations.remove(account);
+            self.env().emit_event(AttestationUpdated {
+                account,
+                attestation: None,
+            });
+            Ok(())
+        }
+
+        /// Stored attestation of an account, expired or not
+        #[ink(message)]
+        pub fn attestation_of(&self, account: AccountId) -> Option<Attestation> {
+            self.attestations.get(account)
+        }
+
+        /// Verification level of an account, 0 without a valid attestation
+        #[ink(message)]
+        pub fn verification_level(&self, account: AccountId) -> u8 {
+            self.valid_attestation(&account).map(|a| a.level).unwrap_or(0)
+        }
+
+        /// Attestation of an account if it has not expired
+        fn valid_attestation(&self, account: &AccountId) -> Option<Attestation> {
+            self.attestations
+                .get(account)
+                .filter(|a| a.expires_at >= self.env().block_timestamp())
+        }
+
+        /// Require transfer messages to carry a native protocol fee, forwarded
+        /// to a treasury; `None` turns the fee off (owner or grantee)
+        #[ink(message)]
+        pub fn set_native_fee(&mut self, fee: Option<NativeFee>) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_native_fee"))?;
+            self.native_fee.set(&fee.filter(|fee| fee.amount > 0));
+            Ok(())
+        }
+
+        /// Native protocol fee settings
+        #[ink(message)]
+        pub fn native_fee(&self) -> Option<NativeFee> {
+            self.native_fee.get().flatten()
+        }
+
+        /// Native value each transfer message must carry, 0 when disabled
+        #[ink(message)]
+        pub fn required_native_fee(&self) -> Balance {
+            self.native_fee().map(|fee| fee.amount).unwrap_or(0)
+        }
+
+        /// Check the native value sent with a transfer message against the
+        /// protocol fee and forward it to the treasury
+        fn collect_native_fee(&mut self) -> Result<()> {
+            let paid = self.env().transferred_value();
+            let Some(fee) = self.native_fee() else {
+                // nothing is owed, so refuse value that would be stranded here
+                return if paid == 0 { Ok(()) } else { Err(Error::NativeFeeMismatch) };
+            };
+            if paid != fee.amount {
+                return Err(Error::NativeFeeMismatch);
+            }
+            self.env()
+                .transfer(fee.treasury, paid)
+                .map_err(|_| Error::NativeTransferFailed)
+        }
+
+        /// Let `account` call the admin message with `selector`, optionally
+        /// until `expires_at` (admin only)
+        #[ink(message)]
+        pub fn grant_permission(
+            &mut self,
+            account: AccountId,
+            selector: MessageSelector,
+            expires_at: Option<Timestamp>,
+        ) -> Result<()> {
+            self.ensure_admin()?;
+            self.permissions
+                .insert((account, selector), &expires_at.unwrap_or(Timestamp::MAX));
+            self.env().emit_event(PermissionGranted {
+                account,
+                selector,
+                expires_at,
+            });
+            Ok(())
+        }
+
+        /// Withdraw a permission grant (admin only)
+        #[ink(message)]
+        pub fn revoke_permission(
+            &mut self,
+            account: AccountId,
+            selector: MessageSelector,
+        ) -> Result<()> {
+            self.ensure_admin()?;
+            self.permissions.remove((account, selector));
+            self.env().emit_event(PermissionRevoked { account, selector });
+            Ok(())
+        }
+
+        /// Expiry of a permission grant, `Timestamp::MAX` if it never expires
+        #[ink(message)]
+        pub fn permission_expiry(
+            &self,
+            account: AccountId,
+            selector: MessageSelector,
+        ) -> Option<Timestamp> {
+            self.permissions.get((account, selector))
+        }
+
+        /// Whether `account` may currently call the admin message with `selector`
+        #[ink(message)]
+        pub fn is_permitted(&self, account: AccountId, selector: MessageSelector) -> bool {
+            self.has_role(DEFAULT_ADMIN_ROLE, account)
+                || role_for(selector).is_some_and(|role| self.has_role(role, account))
+                || self
+                    .permissions
+                    .get((account, selector))
+                    .is_some_and(|expiry| expiry >= self.env().block_timestamp())
+        }
+
+        /// Guard for admin messages: passes admins, holders of the role for
+        /// `selector` and its unexpired grantees, and returns the caller.
+        /// Timelocked messages are refused while a timelock delay is set.
+        fn ensure_permitted(&self, selector: MessageSelector) -> Result<AccountId> {
+            let caller = self.env().caller();
+            if !self.is_permitted(caller, selector) {
+                return Err(Error::NotOwner);
+            }
+            if is_timelocked(selector) && self.timelock_delay.get_or_default() > 0 {
+                return Err(Error::Timelocked);
+            }
+            Ok(caller)
+        }
+
+        /// Guard for role and permission management
+        fn ensure_admin(&self) -> Result<AccountId> {
+            let caller = self.env().caller();
+            if !self.has_role(DEFAULT_ADMIN_ROLE, caller) {
+                return Err(Error::NotOwner);
+            }
+            Ok(caller)
+        }
+
+        /// Give `account` a role (admin only)
+        #[ink(message)]
+        pub fn grant_role(&mut self, role: RoleId, account: AccountId) -> Result<()> {
+            let caller = self.ensure_admin()?;
+            self.roles.insert((role, account), &());
+            self.env().emit_event(RoleGranted {
+                role,
+                account,
+                sender: caller,
+            });
+            Ok(())
+        }
+
+        /// Take a role away from `account` (admin only). The owner's implicit
+        /// admin role cannot be revoked.
+        #[ink(message)]
+        pub fn revoke_role(&mut self, role: RoleId, account: AccountId) -> Result<()> {
+            let caller = self.ensure_admin()?;
+            self.roles.remove((role, account));
+            self.env().emit_event(RoleRevoked {
+                role,
+                account,
+                sender: caller,
+            });
+            Ok(())
+        }
+
+        /// Whether `account` holds `role`
+        #[ink(message)]
+        pub fn has_role(&self, role: RoleId, account: AccountId) -> bool {
+            (role == DEFAULT_ADMIN_ROLE && account == self.owner)
+                || self.roles.contains((role, account))
+        }
+
+        /// Register or drop a contract notified of every supply change
+        /// (owner or grantee)
+        #[ink(message)]
+        pub fn set_supply_observer(
+            &mut self,
+            observer: AccountId,
+            enabled: bool,
+        ) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_supply_observer"))?;
+            let mut observers = self.supply_observers.get_or_default();
+            let known = observers.contains(&observer);
+            if enabled && !known {
+                if observers.len() >= MAX_SUPPLY_OBSERVERS {
+                    return Err(Error::TooManySupplyObservers);
+                }
+                observers.push(observer);
+            } else if !enabled {
+                observers.retain(|o| *o != observer);
+            }
+            self.supply_observers.set(&observers);
+            self.env().emit_event(SupplyObserverUpdated { observer, enabled });
+            Ok(())
+        }
+
+        /// Contracts notified of supply changes
+        #[ink(message)]
+        pub fn supply_observers(&self) -> Vec<AccountId> {
+            self.supply_observers.get_or_default()
+        }
+
+        /// Register or drop a contract whose `TransferHook` messages are
+        /// called before and after every transfer, mint and burn (owner or
+        /// grantee)
+        #[ink(message)]
+        pub fn set_transfer_hook(&mut self, hook: AccountId, enabled: bool) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_transfer_hook"))?;
+            let mut hooks = self.transfer_hooks.get_or_default();
+            let known = hooks.contains(&hook);
+            if enabled && !known {
+                if hooks.len() >= MAX_TRANSFER_HOOKS {
+                    return Err(Error::TooManyTransferHooks);
+                }
+                hooks.push(hook);
+            } else if !enabled {
+                hooks.retain(|h| *h != hook);
+            }
+            self.transfer_hooks.set(&hooks);
+            self.env().emit_event(TransferHookUpdated { hook, enabled });
+            Ok(())
+        }
+
+        /// Contracts called around every balance change
+        #[ink(message)]
+        pub fn transfer_hooks(&self) -> Vec<AccountId> {
+            self.transfer_hooks.get_or_default()
+        }
+
+        /// Allow or disallow `operator` to place holds on the caller's balance
+        #[ink(message)]
+        pub fn set_hold_operator(
+            &mut self,
+            operator: AccountId,
+            allowed: bool,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            if allowed {
+                self.hold_operators.insert((caller, operator), &());
+            } else {
+                self.hold_operators.remove((caller, operator));
+            }
+            Ok(())
+        }
+
+        /// Whether `operator` may place holds on `account`'s balance
+        #[ink(message)]
+        pub fn is_hold_operator(&self, account: AccountId, operator: AccountId) -> bool {
+            self.hold_operators.contains((account, operator))
+        }
+
+        /// Reserve `amount` of `account`'s spendable balance under `hold_id`
+        /// (the account itself or one of its hold operators). Held funds stay
+        /// with the account but cannot be sent or burned until released.
+        #[ink(message)]
+        pub fn hold(
+            &mut self,
+            account: AccountId,
+            amount: TokenBalance,
+            hold_id: [u8; 32],
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            if caller != account && !self.hold_operators.contains((account, caller)) {
+                return Err(Error::NotHoldOperator);
+            }
+            if self.holds.contains(hold_id) {
+                return Err(Error::HoldExists);
+            }
+            if self.spendable_balance(&account) < amount {
+                return Err(Error::InsufficientBalance);
+            }
+            let held = self.held_balance_of(account).saturating_add(amount);
+            self.held_balances.insert(account, &held);
+            self.holds.insert(
+                hold_id,
+                &Hold {
+                    account,
+                    operator: caller,
+                    amount,
+                    created_at: self.env().block_timestamp(),
+                },
+            );
+            self.env().emit_event(HoldPlaced {
+                hold_id,
+                account,
+                operator: caller,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Drop a hold, freeing its funds (whoever placed it)
+        #[ink(message)]
+        pub fn release(&mut self, hold_id: [u8; 32]) -> Result<()> {
+            self.take_hold(hold_id)?;
+            self.env().emit_event(HoldReleased { hold_id });
+            Ok(())
+        }
+
+        /// Pay out a hold's funds from the held account to `to` (whoever
+        /// placed it)
+        #[ink(message)]
+        pub fn execute_hold(&mut self, hold_id: [u8; 32], to: AccountId) -> Result<()> {
+            let hold = self.take_hold(hold_id)?;
+            self.can_transfer(&hold.account, &to, hold.amount)?;
+            self.transfer_internal(hold.account, to, hold.amount)?;
+            self.env().emit_event(HoldExecuted { hold_id, to });
+            Ok(())
+        }
+
+        /// Remove a hold placed by the caller and unreserve its amount
+        fn take_hold(&mut self, hold_id: [u8; 32]) -> Result<Hold> {
+            let hold = self.holds.get(hold_id).ok_or(Error::UnknownHold)?;
+            if hold.operator != self.env().caller() {
+                return Err(Error::NotHoldOperator);
+            }
+            self.holds.remove(hold_id);
+            let held = self.held_balance_of(hold.account).saturating_sub(hold.amount);
+            self.held_balances.insert(hold.account, &held);
+            Ok(hold)
+        }
+
+        /// Hold details
+        #[ink(message)]
+        pub fn get_hold(&self, hold_id: [u8; 32]) -> Option<Hold> {
+            self.holds.get(hold_id)
+        }
+
+        /// Total of an account's balance reserved by holds
+        #[ink(message)]
+        pub fn held_balance_of(&self, account: AccountId) -> TokenBalance {
+            self.held_balances.get(account).unwrap_or(0)
+        }
+
+        /// Request `amount` from `payer`, payable until `expiry`; returns the
+        /// invoice id
+        #[ink(message)]
+        pub fn create_invoice(
+            &mut self,
+            payer: AccountId,
+            amount: TokenBalance,
+            memo: String,
+            expiry: Timestamp,
+        ) -> Result<u32> {
+            let payee = self.env().caller();
+            if memo.len() > MAX_MEMO_LEN {
+                return Err(Error::MemoTooLong);
+            }
+            if expiry <= self.env().block_timestamp() {
+                return Err(Error::Expired);
+            }
+            let mut payer_ids = self.open_invoice_ids(self.payer_invoices.get(payer));
+            let mut payee_ids = self.open_invoice_ids(self.payee_invoices.get(payee));
+            if payer_ids.len() >= MAX_OPEN_INVOICES || payee_ids.len() >= MAX_OPEN_INVOICES {
+                return Err(Error::TooManyInvoices);
+            }
+            let invoice_id = self.invoice_count.get_or_default();
+            self.invoices.insert(
+                invoice_id,
+                &Invoice {
+                    payee,
+                    payer,
+                    amount,
+                    memo,
+                    expiry,
+                    status: InvoiceStatus::Open,
+                },
+            );
+            let next_id = invoice_id.checked_add(1).ok_or(Error::Overflow)?;
+            self.invoice_count.set(&next_id);
+            payer_ids.push(invoice_id);
+            payee_ids.push(invoice_id);
+            self.payer_invoices.insert(payer, &payer_ids);
+            self.payee_invoices.insert(payee, &payee_ids);
+            self.env().emit_event(InvoiceCreated {
+                invoice_id,
+                payee,
+                payer,
+                amount,
+            });
+            Ok(invoice_id)
+        }
+
+        /// Pay an open invoice addressed to the caller
+        #[ink(message)]
+        pub fn pay_invoice(&mut self, invoice_id: u32) -> Result<()> {
+            let caller = self.env().caller();
+            let invoice = self.invoices.get(invoice_id).ok_or(Error::UnknownInvoice)?;
+            if invoice.payer != caller {
+                return Err(Error::NotInvoiceParty);
+            }
+            if invoice.status != InvoiceStatus::Open {
+                return Err(Error::InvoiceClosed);
+            }
+            if invoice.expiry < self.env().block_timestamp() {
+                return Err(Error::Expired);
+            }
+            self.can_transfer(&caller, &invoice.payee, invoice.amount)?;
+            self.transfer_internal(caller, invoice.payee, invoice.amount)?;
+            self.close_invoice(invoice_id, invoice, InvoiceStatus::Paid);
+            Ok(())
+        }
+
+        /// Withdraw (payee) or decline (payer) an open invoice
+        #[ink(message)]
+        pub fn cancel_invoice(&mut self, invoice_id: u32) -> Result<()> {
+            let caller = self.env().caller();
+            let invoice = self.invoices.get(invoice_id).ok_or(Error::UnknownInvoice)?;
+            if invoice.payer != caller && invoice.payee != caller {
+                return Err(Error::NotInvoiceParty);
+            }
+            if invoice.status != InvoiceStatus::Open {
+                return Err(Error::InvoiceClosed);
+            }
+            self.close_invoice(invoice_id, invoice, InvoiceStatus::Cancelled);
+            Ok(())
+        }
+
+        /// Invoice details
+        #[ink(message)]
+        pub fn get_invoice(&self, invoice_id: u32) -> Option<Invoice> {
+            self.invoices.get(invoice_id)
+        }
+
+        /// Unexpired open invoices a payer owes, as (id, invoice)
+        #[ink(message)]
+        pub fn open_invoices_of_payer(&self, payer: AccountId) -> Vec<(u32, Invoice)> {
+            self.open_invoices(self.payer_invoices.get(payer))
+        }
+
+        /// Unexpired open invoices issued by a payee, as (id, invoice)
+        #[ink(message)]
+        pub fn open_invoices_of_payee(&self, payee: AccountId) -> Vec<(u32, Invoice)> {
+            self.open_invoices(self.payee_invoices.get(payee))
+        }
+
+        fn open_invoices(&self, ids: Option<Vec<u32>>) -> Vec<(u32, Invoice)> {
+            self.open_invoice_ids(ids)
+                .into_iter()
+                .filter_map(|id| self.invoices.get(id).map(|invoice| (id, invoice)))
+                .collect()
+        }
+
+        /// Ids from an open-invoice list that are still open and unexpired
+        fn open_invoice_ids(&self, ids: Option<Vec<u32>>) -> Vec<u32> {
+            let now = self.env().block_timestamp();
+            let mut ids = ids.unwrap_or_default();
+            ids.retain(|id| {
+                self.invoices
+                    .get(id)
+                    .is_some_and(|i| i.status == InvoiceStatus::Open && i.expiry >= now)
+            });
+            ids
+        }
+
+        /// Record the final status and drop the invoice from both open lists
+        fn close_invoice(
+            &mut self,
+            invoice_id: u32,
+            mut invoice: Invoice,
+            status: InvoiceStatus,
+        ) {
+            invoice.status = status;
+            self.invoices.insert(invoice_id, &invoice);
+            let payer_ids = self.open_invoice_ids(self.payer_invoices.get(invoice.payer));
+            self.payer_invoices.insert(invoice.payer, &payer_ids);
+            let payee_ids = self.open_invoice_ids(self.payee_invoices.get(invoice.payee));
+            self.payee_invoices.insert(invoice.payee, &payee_ids);
+            self.env().emit_event(InvoiceClosed { invoice_id, status });
+        }
+
+        /// Grant or revoke the arbiter role for reversible transfers (owner
+        /// or grantee)
+        #[ink(message)]
+        pub fn set_arbiter(&mut self, account: AccountId, enabled: bool) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_arbiter"))?;
+            if enabled {
+                self.arbiters.insert(account, &());
+            } else {
+                self.arbiters.remove(account);
+            }
+            self.env().emit_event(ArbiterUpdated { account, enabled });
+            Ok(())
+        }
+
+        /// Whether an account can reverse pending reversible transfers
+        #[ink(message)]
+        pub fn is_arbiter(&self, account: AccountId) -> bool {
+            self.arbiters.contains(account)
+        }
+
+        /// Send `amount` into contract custody for `to`. During the next
+        /// `window` ms the sender or an arbiter can reverse it; afterwards the
+        /// recipient claims it. Returns the transfer id.
+        #[ink(message, payable)]
+        pub fn transfer_reversible(
+            &mut self,
+            to: AccountId,
+            amount: TokenBalance,
+            window: Timestamp,
+        ) -> Result<u32> {
+            self.collect_native_fee()?;
+            let caller = self.env().caller();
+            self.can_transfer(&caller, &to, amount)?;
+            self.debit(caller, amount)?;
+            self.record_spending(caller, amount);
+            self.deliver(caller, self.env().account_id(), amount)?;
+
+            let transfer_id = self.reversible_transfer_count.get_or_default();
+            let claimable_at = self.env().block_timestamp().saturating_add(window);
+            self.reversible_transfers.insert(
+                transfer_id,
+                &ReversibleTransfer {
+                    from: caller,
+                    to,
+                    amount,
+                    claimable_at,
+                },
+            );
+            let next_id = transfer_id.checked_add(1).ok_or(Error::Overflow)?;
+            self.reversible_transfer_count.set(&next_id);
+            self.env().emit_event(ReversibleTransferCreated {
+                transfer_id,
+                from: caller,
+                to,
+                amount,
+                claimable_at,
+            });
+            Ok(transfer_id)
+        }
+
+        /// Send a pending transfer back to its sender before the window ends
+        /// (sender or arbiter)
+        #[ink(message)]
+        pub fn reverse_transfer(&mut self, transfer_id: u32) -> Result<()> {
+            let caller = self.env().caller();
+            let pending = self
+                .reversible_transfers
+                .get(transfer_id)
+                .ok_or(Error::UnknownTransfer)?;
+            if caller != pending.from && !self.arbiters.contains(caller) {
+                return Err(Error::NotTransferParty);
+            }
+            if self.env().block_timestamp() >= pending.claimable_at {
+                return Err(Error::Expired);
+            }
+            self.reversible_transfers.remove(transfer_id);
+            let custody = self.env().account_id();
+            self.debit(custody, pending.amount)?;
+            self.deliver(custody, pending.from, pending.amount)?;
+            self.env().emit_event(ReversibleTransferSettled {
+                transfer_id,
+                reversed: true,
+            });
+            Ok(())
+        }
+
+        /// Collect a pending transfer once its window has ended (recipient only)
+        #[ink(message)]
+        pub fn claim_transfer(&mut self, transfer_id: u32) -> Result<()> {
+            let caller = self.env().caller();
+            let pending = self
+                .reversible_transfers
+                .get(transfer_id)
+                .ok_or(Error::UnknownTransfer)?;
+            if caller != pending.to {
+                return Err(Error::NotTransferParty);
+            }
+            if self.env().block_timestamp() < pending.claimable_at {
+                return Err(Error::ChallengePeriodActive);
+            }
+            self.reversible_transfers.remove(transfer_id);
+            let custody = self.env().account_id();
+            self.can_transfer(&custody, &pending.to, pending.amount)?;
+            self.transfer_internal(custody, pending.to, pending.amount)?;
+            self.env().emit_event(ReversibleTransferSettled {
+                transfer_id,
+                reversed: false,
+            });
+            Ok(())
+        }
+
+        /// Pending reversible transfer details
+        #[ink(message)]
+        pub fn reversible_transfer(&self, transfer_id: u32) -> Option<ReversibleTransfer> {
+            self.reversible_transfers.get(transfer_id)
+        }
+
+        /// Register a distribution job with a first set of payouts, funded
+        /// from the caller's balance into contract custody (owner or grantee).
+        /// Returns the job id.
+        #[ink(message)]
+        pub fn create_distribution(
+            &mut self,
+            recipients: Vec<AccountId>,
+            amounts: Vec<TokenBalance>,
+        ) -> Result<u32> {
+            let caller = self.ensure_permitted(ink::selector_bytes!("create_distribution"))?;
+            let job_id = self.distribution_job_count.get_or_default();
+            let next_id = job_id.checked_add(1).ok_or(Error::Overflow)?;
+            self.distribution_job_count.set(&next_id);
+            self.distribution_jobs.insert(
+                job_id,
+                &DistributionJob {
+                    creator: caller,
+                    entries: 0,
+                    processed: 0,
+                    skipped: 0,
+                    total: 0,
+                    paid: 0,
+                },
+            );
+            self.add_distribution_entries(job_id, recipients, amounts)?;
+            Ok(job_id)
+        }
+
+        /// Append and fund more payouts to a distribution job (creator only)
+        #[ink(message)]
+        pub fn add_distribution_entries(
+            &mut self,
+            job_id: u32,
+            recipients: Vec<AccountId>,
+            amounts: Vec<TokenBalance>,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            let mut job = self
+                .distribution_jobs
+                .get(job_id)
+                .ok_or(Error::UnknownDistribution)?;
+            if job.creator != caller {
+                return Err(Error::NotDistributionCreator);
+            }
+            if recipients.len() != amounts.len() {
+                return Err(Error::LengthMismatch);
+            }
+            let mut funding: TokenBalance = 0;
+            for (to, amount) in recipients.into_iter().zip(amounts) {
+                funding = funding.checked_add(amount).ok_or(Error::Overflow)?;
+                self.distribution_entries.insert((job_id, job.entries), &(to, amount));
+                job.entries = job.entries.checked_add(1).ok_or(Error::Overflow)?;
+            }
+            let custody = self.env().account_id();
+            self.can_transfer(&caller, &custody, funding)?;
+            self.debit(caller, funding)?;
+            self.record_spending(caller, funding);
+            self.deliver(caller, custody, funding)?;
+            job.total = job.total.saturating_add(funding);
+            self.distribution_jobs.insert(job_id, &job);
+            self.env().emit_event(DistributionProgress {
+                job_id,
+                entries: job.entries,
+                processed: job.processed,
+                paid: job.paid,
+            });
+            Ok(())
+        }
+
+        /// Pay out the next `n` entries of a distribution job (callable by
+        /// anyone). Entries whose recipient is blacklisted are refunded to the
+        /// creator. Returns the number of entries still pending.
+        #[ink(message)]
+        pub fn process_next(&mut self, job_id: u32, n: u32) -> Result<u32> {
+            if self.is_paused() {
+                return Err(Error::Paused);
+            }
+            let mut job = self
+                .distribution_jobs
+                .get(job_id)
+                .ok_or(Error::UnknownDistribution)?;
+            let custody = self.env().account_id();
+            let end = job.processed.saturating_add(n).min(job.entries);
+            for index in job.processed..end {
+                let Some((to, amount)) = self.distribution_entries.take((job_id, index)) else {
+                    continue;
+                };
+                self.debit(custody, amount)?;
+                if self.blacklist.get(to).unwrap_or(false) {
+                    self.deliver(custody, job.creator, amount)?;
+                    job.skipped = job.skipped.saturating_add(1);
+                } else {
+                    self.deliver(custody, to, amount)?;
+                    job.paid = job.paid.saturating_add(amount);
+                }
+            }
+            job.processed = end;
+            self.distribution_jobs.insert(job_id, &job);
+            self.env().emit_event(DistributionProgress {
+                job_id,
+                entries: job.entries,
+                processed: job.processed,
+                paid: job.paid,
+            });
+            Ok(job.entries.saturating_sub(job.processed))
+        }
+
+        /// Distribution job progress
+        #[ink(message)]
+        pub fn distribution_job(&self, job_id: u32) -> Option<DistributionJob> {
+            self.distribution_jobs.get(job_id)
+        }
+
+        /// Require `cosigner`'s approval for the caller's outgoing transfers
+        /// above `threshold`. A policy can only be tightened by the account
+        /// (lower threshold, same co-signer); anything else needs the current
+        /// co-signer to call `release_cosign_policy` first.
+        #[ink(message)]
+        pub fn set_cosign_policy(&mut self, policy: CosignPolicy) -> Result<()> {
+            let caller = self.env().caller();
+            if let Some(current) = self.cosign_policies.get(caller) {
+                if policy.cosigner != current.cosigner || policy.threshold > current.threshold {
+                    return Err(Error::NotCosigner);
+                }
+            }
+            self.cosign_policies.insert(caller, &policy);
+            self.env().emit_event(CosignPolicyChanged {
+                account: caller,
+                policy: Some(policy),
+            });
+            Ok(())
+        }
+
+        /// Remove an account's co-signing policy (its co-signer only)
+        #[ink(message)]
+        pub fn release_cosign_policy(&mut self, account: AccountId) -> Result<()> {
+            let caller = self.env().caller();
+            let policy = self.cosign_policies.get(account).ok_or(Error::NotCosigner)?;
+            if policy.cosigner != caller {
+                return Err(Error::NotCosigner);
+            }
+            self.cosign_policies.remove(account);
+            self.env().emit_event(CosignPolicyChanged {
+                account,
+                policy: None,
+            });
+            Ok(())
+        }
+
+        /// Co-signing policy of an account
+        #[ink(message)]
+        pub fn cosign_policy_of(&self, account: AccountId) -> Option<CosignPolicy> {
+            self.cosign_policies.get(account)
+        }
+
+        /// Start a transfer that needs co-signing; returns its id
+        #[ink(message)]
+        pub fn request_cosigned_transfer(
+            &mut self,
+            to: AccountId,
+            amount: TokenBalance,
+        ) -> Result<u32> {
+            let caller = self.env().caller();
+            let transfer_id = self.cosigned_transfer_count.get_or_default();
+            let next_id = transfer_id.checked_add(1).ok_or(Error::Overflow)?;
+            self.cosigned_transfer_count.set(&next_id);
+            self.cosigned_transfers.insert(
+                transfer_id,
+                &CosignedTransfer {
+                    from: caller,
+                    to,
+                    amount,
+                    approved: false,
+                },
+            );
+            self.env().emit_event(CosignedTransferRequested {
+                transfer_id,
+                from: caller,
+                to,
+                amount,
+            });
+            Ok(transfer_id)
+        }
+
+        /// Approve a pending transfer (the sender's co-signer only)
+        #[ink(message)]
+        pub fn cosign_transfer(&mut self, transfer_id: u32) -> Result<()> {
+            let caller = self.env().caller();
+            let mut pending = self
+                .cosigned_transfers
+                .get(transfer_id)
+                .ok_or(Error::UnknownTransfer)?;
+            let policy = self.cosign_policies.get(pending.from);
+            if policy.map(|p| p.cosigner) != Some(caller) {
+                return Err(Error::NotCosigner);
+            }
+            pending.approved = true;
+            self.cosigned_transfers.insert(transfer_id, &pending);
+            self.env().emit_event(CosignedTransferApproved { transfer_id });
+            Ok(())
+        }
+
+        /// Carry out a co-signed transfer (sender only)
+        #[ink(message, payable)]
+        pub fn execute_cosigned_transfer(&mut self, transfer_id: u32) -> Result<()> {
+            self.collect_native_fee()?;
+            let caller = self.env().caller();
+            let pending = self
+                .cosigned_transfers
+                .get(transfer_id)
+                .ok_or(Error::UnknownTransfer)?;
+            if pending.from != caller {
+                return Err(Error::NotTransferParty);
+            }
+            if !pending.approved {
+                return Err(Error::NotCosigned);
+            }
+            self.cosigned_transfers.remove(transfer_id);
+            self.check_restrictions(&caller, &pending.to, pending.amount)?;
+            self.transfer_internal(caller, pending.to, pending.amount)?;
+            self.env().emit_event(CosignedTransferClosed {
+                transfer_id,
+                executed: true,
+            });
+            Ok(())
+        }
+
+        /// Drop a pending co-signed transfer (sender or co-signer)
+        #[ink(message)]
+        pub fn cancel_cosigned_transfer(&mut self, transfer_id: u32) -> Result<()> {
+            let caller = self.env().caller();
+            let pending = self
+                .cosigned_transfers
+                .get(transfer_id)
+                .ok_or(Error::UnknownTransfer)?;
+            let cosigner = self.cosign_policies.get(pending.from).map(|p| p.cosigner);
+            if caller != pending.from && Some(caller) != cosigner {
+                return Err(Error::NotTransferParty);
+            }
+            self.cosigned_transfers.remove(transfer_id);
+            self.env().emit_event(CosignedTransferClosed {
+                transfer_id,
+                executed: false,
+            });
+            Ok(())
+        }
+
+        /// Pending co-signed transfer details
+        #[ink(message)]
+        pub fn cosigned_transfer(&self, transfer_id: u32) -> Option<CosignedTransfer> {
+            self.cosigned_transfers.get(transfer_id)
+        }
+
+        /// Set `owner`'s allowance for `spender` from an ECDSA signature by
+        /// the owner over `permit_payload`; anyone can submit it. Each permit
+        /// consumes the owner's current permit nonce.
+        #[ink(message)]
+        pub fn permit(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            amount: TokenBalance,
+            deadline: Timestamp,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            if self.env().block_timestamp() > deadline {
+                return Err(Error::Expired);
+            }
+            let nonce = self.nonce_of(owner);
+            let payload = self.permit_payload(owner, spender, amount, nonce, deadline);
+            if !self.is_signed_by(&owner, &payload, &signature) {
+                return Err(Error::InvalidSignature);
+            }
+            self.use_nonce(owner)?;
+            self.approve_until(owner, spender, amount, None)
+        }
+
+        /// Bytes an owner signs (ECDSA) to issue a permit
+        #[ink(message)]
+        pub fn permit_payload(
+            &self,
+            owner: AccountId,
+            spender: AccountId,
+            amount: TokenBalance,
+            nonce: u64,
+            deadline: Timestamp,
+        ) -> Vec<u8> {
+            ink::scale::Encode::encode(&(
+                PERMIT_DOMAIN,
+                self.env().account_id(),
+                owner,
+                spender,
+                amount,
+                nonce,
+                deadline,
+            ))
+        }
+
+        /// Set (or clear with `None`) the relayer allowed to submit signed
+        /// meta-transfers (owner or grantee)
+        #[ink(message)]
+        pub fn set_trusted_forwarder(&mut self, forwarder: Option<AccountId>) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_trusted_forwarder"))?;
+            self.trusted_forwarder.set(&forwarder);
+            Ok(())
+        }
+
+        /// Relayer allowed to submit signed meta-transfers
+        #[ink(message)]
+        pub fn trusted_forwarder(&self) -> Option<AccountId> {
+            self.trusted_forwarder.get().flatten()
+        }
+
+        /// Account a transfer is made on behalf of: the signer while a
+        /// meta-transfer executes, the caller otherwise
+        fn effective_caller(&self) -> AccountId {
+            self.meta_sender
+                .get()
+                .flatten()
+                .unwrap_or_else(|| self.env().caller())
+        }
+
+        /// Transfer on behalf of `from`, who signed `meta_transfer_payload`
+        /// off-chain (trusted forwarder only). Each meta-transfer consumes
+        /// the signer's current nonce, shared with permits.
+        #[ink(message, payable)]
+        pub fn execute_meta_transfer(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            amount: TokenBalance,
+            deadline: Timestamp,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            let relayer = self.env().caller();
+            if self.trusted_forwarder() != Some(relayer) {
+                return Err(Error::NotTrustedForwarder);
+            }
+            if self.env().block_timestamp() > deadline {
+                return Err(Error::Expired);
+            }
+            let nonce = self.nonce_of(from);
+            let payload = self.meta_transfer_payload(from, to, amount, nonce, deadline);
+            if !self.is_signed_by(&from, &payload, &signature) {
+                return Err(Error::InvalidSignature);
+            }
+            self.use_nonce(from)?;
+
+            self.collect_native_fee()?;
+            self.meta_sender.set(&Some(from));
+            let sent = self.send(to, amount);
+            self.meta_sender.set(&None);
+            sent?;
+            self.env().emit_event(MetaTransferExecuted {
+                from,
+                to,
+                amount,
+                nonce,
+                relayer,
+            });
+            Ok(())
+        }
+
+        /// Bytes a sender signs (ECDSA) to authorize a meta-transfer
+        #[ink(message)]
+        pub fn meta_transfer_payload(
+            &self,
+            from: AccountId,
+            to: AccountId,
+            amount: TokenBalance,
+            nonce: u64,
+            deadline: Timestamp,
+        ) -> Vec<u8> {
+            ink::scale::Encode::encode(&(
+                META_TRANSFER_DOMAIN,
+                self.env().account_id(),
+                from,
+                to,
+                amount,
+                nonce,
+                deadline,
+            ))
+        }
+
+        /// Nonce the next permit of `owner` must be signed with
+        #[ink(message)]
+        pub fn permit_nonce(&self, owner: AccountId) -> u64 {
+            self.nonce_of(owner)
+        }
+
+        /// Nonce the next signed operation (permit or meta-transfer) of
+        /// `account` must carry; each accepted operation increments it
+        #[ink(message)]
+        pub fn nonce_of(&self, account: AccountId) -> u64 {
+            self.permit_nonces.get(account).unwrap_or(0)
+        }
+
+        /// Consume `account`'s current nonce
+        fn use_nonce(&mut self, account: AccountId) -> Result<u64> {
+            let nonce = self.nonce_of(account);
+            self.permit_nonces
+                .insert(account, &nonce.checked_add(1).ok_or(Error::Overflow)?);
+            self.env().emit_event(NonceUsed { account, nonce });
+            Ok(nonce)
+        }
+
+        /// Mint `total` into custody, vesting linearly to `beneficiary` over
+        /// `duration` from `start`, with nothing claimable before `cliff` has
+        /// passed (owner or grantee). One schedule per beneficiary; only
+        /// `revocable` ones can later be cut short by `revoke_vesting`.
+        #[ink(message)]
+        pub fn create_vesting(
+            &mut self,
+            beneficiary: AccountId,
+            total: TokenBalance,
+            start: Timestamp,
+            cliff: Timestamp,
+            duration: Timestamp,
+            revocable: bool,
+        ) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("create_vesting"))?;
+            if duration == 0 || cliff > duration {
+                return Err(Error::InvalidVestingSchedule);
+            }
+            if self.vesting.contains(beneficiary) {
+                return Err(Error::VestingExists);
+            }
+            self.mint_internal(self.env().account_id(), total, None)?;
+            let schedule = VestingSchedule {
+                total,
+                released: 0,
+                start,
+                cliff,
+                duration,
+                revocable,
+                revoked: false,
+            };
+            self.vesting.insert(beneficiary, &schedule);
+            self.env().emit_event(VestingCreated {
+                beneficiary,
+                total,
+                start,
+                cliff,
+                duration,
+            });
+            Ok(())
+        }
+
+        /// Release the caller's vested but unclaimed tokens. Returns the
+        /// amount released.
+        #[ink(message)]
+        pub fn claim_vested(&mut self) -> Result<TokenBalance> {
+            let caller = self.env().caller();
+            let mut schedule = self.vesting.get(caller).ok_or(Error::UnknownVesting)?;
+            let amount = schedule
+                .vested_at(self.env().block_timestamp())
+                .saturating_sub(schedule.released);
+            if amount == 0 {
+                return Ok(0);
+            }
+            schedule.released = schedule.released.saturating_add(amount);
+            self.vesting.insert(caller, &schedule);
+            let custody = self.env().account_id();
+            self.debit(custody, amount)?;
+            self.deliver(custody, caller, amount)?;
+            self.env().emit_event(VestingClaimed {
+                beneficiary: caller,
+                amount,
+            });
+            Ok(amount)
+        }
+
+        /// Stop a revocable schedule: what has vested stays claimable by the
+        /// beneficiary, the unvested remainder goes to the treasury, or is
+        /// burned when no treasury is set (owner or grantee)
+        #[ink(message)]
+        pub fn revoke_vesting(&mut self, beneficiary: AccountId) -> Result<TokenBalance> {
+            self.ensure_permitted(ink::selector_bytes!("revoke_vesting"))?;
+            let mut schedule = self.vesting.get(beneficiary).ok_or(Error::UnknownVesting)?;
+            if !schedule.revocable || schedule.revoked {
+                return Err(Error::NotRevocable);
+            }
+            let vested = schedule.vested_at(self.env().block_timestamp());
+            let unvested = schedule.total.saturating_sub(vested);
+            schedule.total = vested;
+            schedule.revoked = true;
+            self.vesting.insert(beneficiary, &schedule);
+
+            if unvested > 0 {
+                let custody = self.env().account_id();
+                self.debit(custody, unvested)?;
+                match self.treasury() {
+                    Some(treasury) => {
+                        self.deliver(custody, treasury, unvested)?;
+                        self.note_treasury_inflow(custody, treasury, unvested)?;
+                    }
+                    None => self.reduce_supply(custody, unvested)?,
+                }
+            }
+            self.env().emit_event(VestingRevoked {
+                beneficiary,
+                unvested,
+            });
+            Ok(unvested)
+        }
+
+        /// Amount vested so far for `beneficiary`, claimed or not
+        #[ink(message)]
+        pub fn vested_amount(&self, beneficiary: AccountId) -> TokenBalance {
+            self.vesting
+                .get(beneficiary)
+                .map(|schedule| schedule.vested_at(self.env().block_timestamp()))
+                .unwrap_or(0)
+        }
+
+        /// Vesting schedule of `beneficiary`
+        #[ink(message)]
+        pub fn vesting_schedule(&self, beneficiary: AccountId) -> Option<VestingSchedule> {
+            self.vesting.get(beneficiary)
+        }
+
+        /// Move `amount` of the caller's tokens into its stake
+        #[ink(message)]
+        pub fn stake(&mut self, amount: TokenBalance) -> Result<()> {
+            let caller = self.env().caller();
+            let custody = self.env().account_id();
+            self.can_transfer(&caller, &custody, amount)?;
+            self.debit(caller, amount)?;
+            self.record_spending(caller, amount);
+            self.deliver(caller, custody, amount)?;
+
+            let (mut pool, mut stake) = self.settle_stake(caller);
+            stake.amount = stake.amount.checked_add(amount).ok_or(Error::Overflow)?;
+            pool.total_staked = pool.total_staked.checked_add(amount).ok_or(Error::Overflow)?;
+            self.staking.set(&pool);
+            self.stakes.insert(caller, &stake);
+            self.env().emit_event(Staked {
+                account: caller,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Return `amount` of the caller's stake to its balance. Accrued
+        /// rewards stay claimable.
+        #[ink(message)]
+        pub fn unstake(&mut self, amount: TokenBalance) -> Result<()> {
+            let caller = self.env().caller();
+            let (mut pool, mut stake) = self.settle_stake(caller);
+            if stake.amount < amount {
+                return Err(Error::InsufficientStake);
+            }
+            stake.amount = stake.amount.saturating_sub(amount);
+            pool.total_staked = pool.total_staked.saturating_sub(amount);
+            self.staking.set(&pool);
+            self.stakes.insert(caller, &stake);
+
+            let custody = self.env().account_id();
+            self.debit(custody, amount)?;
+            self.deliver(custody, caller, amount)?;
+            self.env().emit_event(Unstaked {
+                account: caller,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Mint the caller's accrued staking rewards. Returns the amount.
+        #[ink(message)]
+        pub fn claim_rewards(&mut self) -> Result<TokenBalance> {
+            let caller = self.env().caller();
+            let (pool, mut stake) = self.settle_stake(caller);
+            let amount = stake.accrued;
+            stake.accrued = 0;
+            self.staking.set(&pool);
+            self.stakes.insert(caller, &stake);
+            if amount > 0 {
+                self.mint_internal(caller, amount, None)?;
+            }
+            self.env().emit_event(RewardsClaimed {
+                account: caller,
+                amount,
+            });
+            Ok(amount)
+        }
+
+        /// Pool brought up to the current block and `account`'s stake with
+        /// its rewards accrued up to it; callers store both back
+        fn settle_stake(&self, account: AccountId) -> (StakingPool, Stake) {
+            let mut pool = self.staking.get_or_default();
+            pool.index = pool.index_at(self.env().block_number());
+            pool.last_block = self.env().block_number();
+            let mut stake = self.stakes.get(account).unwrap_or_default();
+            stake.accrued = stake.accrued_at(pool.index);
+            stake.index = pool.index;
+            (pool, stake)
+        }
+
+        /// Set the staking reward rate in reward units per staked unit per
+        /// block, scaled by `REWARD_RATE_SCALE` (owner or grantee). Rewards
+        /// up to now accrue at the previous rate.
+        #[ink(message)]
+        pub fn set_reward_rate(&mut self, rate: u128) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_reward_rate"))?;
+            let mut pool = self.staking.get_or_default();
+            pool.index = pool.index_at(self.env().block_number());
+            pool.last_block = self.env().block_number();
+            pool.rate = rate;
+            self.staking.set(&pool);
+            Ok(())
+        }
+
+        /// Global staking state
+        #[ink(message)]
+        pub fn staking_pool(&self) -> StakingPool {
+            self.staking.get_or_default()
+        }
+
+        /// Tokens staked by `account`
+        #[ink(message)]
+        pub fn staked_of(&self, account: AccountId) -> TokenBalance {
+            self.stakes.get(account).map(|stake| stake.amount).unwrap_or(0)
+        }
+
+        /// Staking rewards `account` could claim now
+        #[ink(message)]
+        pub fn pending_rewards(&self, account: AccountId) -> TokenBalance {
+            let index = self.staking.get_or_default().index_at(self.env().block_number());
+            self.stakes
+                .get(account)
+                .map(|stake| stake.accrued_at(index))
+                .unwrap_or(0)
+        }
+
+        /// Delegate the caller's voting power, its whole balance, to `to`.
+        /// Undelegated balances carry no votes; delegate to yourself to vote.
+        #[ink(message)]
+        pub fn delegate(&mut self, to: AccountId) -> Result<()> {
+            let caller = self.env().caller();
+            let previous = self.delegates.get(caller);
+            self.delegates.insert(caller, &to);
+            let balance = self.balances.get(caller).unwrap_or(0);
+            self.move_votes(previous, Some(to), balance);
+            self.env().emit_event(DelegateChanged {
+                delegator: caller,
+                from_delegate: previous,
+                to_delegate: to,
+            });
+            Ok(())
+        }
+
+        /// Move `amount` of voting power between delegates, checkpointing both
+        fn move_votes(
+            &mut self,
+            from: Option<AccountId>,
+            to: Option<AccountId>,
+            amount: TokenBalance,
+        ) {
+            if from == to || amount == 0 {
+                return;
+            }
+            if let Some(delegate) = from {
+                let votes = self.get_votes(delegate);
+                self.write_checkpoint(delegate, votes, votes.saturating_sub(amount));
+            }
+            if let Some(delegate) = to {
+                let votes = self.get_votes(delegate);
+                self.write_checkpoint(delegate, votes, votes.saturating_add(amount));
+            }
+        }
+
+        /// Record `delegate`'s votes as of the current block
+        fn write_checkpoint(
+            &mut self,
+            delegate: AccountId,
+            previous_votes: TokenBalance,
+            new_votes: TokenBalance,
+        ) {
+            let block = self.env().block_number();
+            let mut checkpoints = self.vote_checkpoints.get(delegate).unwrap_or_default();
+            match checkpoints.last_mut() {
+                Some((at, votes)) if *at == block => *votes = new_votes,
+                _ => checkpoints.push((block, new_votes)),
+            }
+            self.vote_checkpoints.insert(delegate, &checkpoints);
+            self.env().emit_event(DelegateVotesChanged {
+                delegate,
+                previous_votes,
+                new_votes,
+            });
+        }
+
+        /// Account `account`'s votes are delegated to
+        #[ink(message)]
+        pub fn delegates(&self, account: AccountId) -> Option<AccountId> {
+            self.delegates.get(account)
+        }
+
+        /// Current voting power of `account`
+        #[ink(message)]
+        pub fn get_votes(&self, account: AccountId) -> TokenBalance {
+            self.vote_checkpoints
+                .get(account)
+                .and_then(|checkpoints| checkpoints.last().map(|(_, votes)| *votes))
+                .unwrap_or(0)
+        }
+
+        /// Voting power of `account` at the end of a past `block`
+        #[ink(message)]
+        pub fn get_past_votes(
+            &self,
+            account: AccountId,
+            block: BlockNumber,
+        ) -> Result<TokenBalance> {
+            if block >= self.env().block_number() {
+                return Err(Error::FutureLookup);
+            }
+            let checkpoints = self.vote_checkpoints.get(account).unwrap_or_default();
+            let index = checkpoints.partition_point(|(at, _)| *at <= block);
+            Ok(match index.checked_sub(1).and_then(|i| checkpoints.get(i)) {
+                Some((_, votes)) => *votes,
+                None => 0,
+            })
+        }
+
+        /// Set the governance voting period and quorum (owner or grantee)
+        #[ink(message)]
+        pub fn set_governance_config(&mut self, config: GovernanceConfig) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_governance_config"))?;
+            self.governance.set(&config);
+            Ok(())
+        }
+
+        /// Current governance voting rules
+        #[ink(message)]
+        pub fn governance_config(&self) -> GovernanceConfig {
+            self.governance.get_or_default()
+        }
+
+        /// Open a proposal to perform the action hashing to `call_hash`
+        /// (see `governance_call_hash`), returns the proposal id
+        #[ink(message)]
+        pub fn propose(&mut self, call_hash: Hash, description: String) -> Result<u32> {
+            let config = self.governance.get_or_default();
+            if config.voting_period == 0 {
+                return Err(Error::GovernanceDisabled);
+            }
+            if description.len() > MAX_DESCRIPTION_LEN {
+                return Err(Error::DescriptionTooLong);
+            }
+            let proposer = self.env().caller();
+            let start_block = self.env().block_number();
+            let end_block = start_block.saturating_add(config.voting_period);
+            let proposal_id = self.proposal_count.get_or_default();
+            self.proposals.insert(
+                proposal_id,
+                &Proposal {
+                    proposer,
+                    call_hash,
+                    description,
+                    start_block,
+                    end_block,
+                    for_votes: 0,
+                    against_votes: 0,
+                    executed: false,
+                },
+            );
+            let next_id = proposal_id.checked_add(1).ok_or(Error::Overflow)?;
+            self.proposal_count.set(&next_id);
+            self.env().emit_event(ProposalCreated {
+                proposal_id,
+                proposer,
+                call_hash,
+                end_block,
+            });
+            Ok(proposal_id)
+        }
+
+        /// Vote on an open proposal with the caller's delegated votes as of
+        /// the proposal's start block
+        #[ink(message)]
+        pub fn vote(&mut self, proposal_id: u32, support: bool) -> Result<()> {
+            let voter = self.env().caller();
+            let mut proposal = self.proposals.get(proposal_id).ok_or(Error::UnknownProposal)?;
+            let block = self.env().block_number();
+            if block <= proposal.start_block || block > proposal.end_block {
+                return Err(Error::VotingClosed);
+            }
+            if self.proposal_votes.contains((proposal_id, voter)) {
+                return Err(Error::AlreadyVoted);
+            }
+            let weight = self.get_past_votes(voter, proposal.start_block)?;
+            if support {
+                proposal.for_votes = proposal.for_votes.saturating_add(weight);
+            } else {
+                proposal.against_votes = proposal.against_votes.saturating_add(weight);
+            }
+            self.proposals.insert(proposal_id, &proposal);
+            self.proposal_votes.insert((proposal_id, voter), &());
+            self.env().emit_event(VoteCast {
+                proposal_id,
+                voter,
+                support,
+                weight,
+            });
+            Ok(())
+        }
+
+        /// Perform the action of a proposal that passed, callable by anyone
+        /// once voting has ended. `action` must match the proposal's call hash.
+        #[ink(message)]
+        pub fn execute(&mut self, proposal_id: u32, action: AdminAction) -> Result<()> {
+            let mut proposal = self.proposals.get(proposal_id).ok_or(Error::UnknownProposal)?;
+            if proposal.executed {
+                return Err(Error::ProposalExecuted);
+            }
+            if self.env().block_number() <= proposal.end_block {
+                return Err(Error::VotingActive);
+            }
+            let config = self.governance.get_or_default();
+            if proposal.for_votes < config.quorum || proposal.for_votes <= proposal.against_votes {
+                return Err(Error::ProposalRejected);
+            }
+            if self.governance_call_hash(action.clone()) != proposal.call_hash {
+                return Err(Error::CallHashMismatch);
+            }
+            proposal.executed = true;
+            self.proposals.insert(proposal_id, &proposal);
+
+            self.apply_action(self.env().account_id(), action)?;
+            self.env().emit_event(ProposalExecuted { proposal_id });
+            Ok(())
+        }
+
+        /// Call hash a proposal for `action` must carry
+        #[ink(message)]
+        pub fn governance_call_hash(&self, action: AdminAction) -> Hash {
+            Hash::from(self.env().hash_encoded::<Blake2x256, _>(&action))
+        }
+
+        /// Proposal details
+        #[ink(message)]
+        pub fn proposal(&self, proposal_id: u32) -> Option<Proposal> {
+            self.proposals.get(proposal_id)
+        }
+
+        /// Whether `voter` voted on a proposal
+        #[ink(message)]
+        pub fn has_voted(&self, proposal_id: u32, voter: AccountId) -> bool {
+            self.proposal_votes.contains((proposal_id, voter))
+        }
+
+        /// Enable the timelock, or set its delay while it is off (owner or
+        /// grantee). Once on, changing the delay is itself timelocked.
+        #[ink(message)]
+        pub fn set_timelock_delay(&mut self, delay: Timestamp) -> Result<()> {
+            let caller = self.ensure_permitted(ink::selector_bytes!("set_timelock_delay"))?;
+            self.apply_action(caller, AdminAction::SetTimelockDelay(delay))
+        }
+
+        /// Delay between scheduling and executing a timelocked action, zero
+        /// when the timelock is off
+        #[ink(message)]
+        pub fn timelock_delay(&self) -> Timestamp {
+            self.timelock_delay.get_or_default()
+        }
+
+        /// Queue an admin action to run once the timelock delay has passed.
+        /// The caller needs the permission of the matching admin message.
+        /// Returns the action id.
+        #[ink(message)]
+        pub fn schedule_action(&mut self, action: AdminAction) -> Result<u32> {
+            let caller = self.env().caller();
+            if !self.is_permitted(caller, action.selector()) {
+                return Err(Error::NotOwner);
+            }
+            let eta = self
+                .env()
+                .block_timestamp()
+                .saturating_add(self.timelock_delay.get_or_default());
+            let action_id = self.scheduled_action_count.get_or_default();
+            self.scheduled_actions.insert(
+                action_id,
+                &ScheduledAction {
+                    action: action.clone(),
+                    scheduled_by: caller,
+                    eta,
+                },
+            );
+            let next_id = action_id.checked_add(1).ok_or(Error::Overflow)?;
+            self.scheduled_action_count.set(&next_id);
+            self.env().emit_event(ActionScheduled {
+                action_id,
+                action,
+                scheduled_by: caller,
+                eta,
+            });
+            Ok(action_id)
+        }
+
+        /// Run a scheduled action whose delay has passed (callable by anyone)
+        #[ink(message)]
+        pub fn execute_action(&mut self, action_id: u32) -> Result<()> {
+            let scheduled = self
+                .scheduled_actions
+                .get(action_id)
+                .ok_or(Error::UnknownAction)?;
+            if self.env().block_timestamp() < scheduled.eta {
+                return Err(Error::TimelockPending);
+            }
+            self.scheduled_actions.remove(action_id);
+            self.apply_action(scheduled.scheduled_by, scheduled.action)?;
+            self.env().emit_event(ActionExecuted { action_id });
+            Ok(())
+        }
+
+        /// Drop a scheduled action (anyone permitted to schedule it)
+        #[ink(message)]
+        pub fn cancel_action(&mut self, action_id: u32) -> Result<()> {
+            let scheduled = self
+                .scheduled_actions
+                .get(action_id)
+                .ok_or(Error::UnknownAction)?;
+            if !self.is_permitted(self.env().caller(), scheduled.action.selector()) {
+                return Err(Error::NotOwner);
+            }
+            self.scheduled_actions.remove(action_id);
+            self.env().emit_event(ActionCancelled { action_id });
+            Ok(())
+        }
+
+        /// Scheduled action details
+        #[ink(message)]
+        pub fn scheduled_action(&self, action_id: u32) -> Option<ScheduledAction> {
+            self.scheduled_actions.get(action_id)
+        }
+
+        /// Perform an admin action whose authorization was already checked,
+        /// attributing it to `by`
+        fn apply_action(&mut self, by: AccountId, action: AdminAction) -> Result<()> {
+            match action {
+                AdminAction::Mint { to, amount } => self.mint_internal(to, amount, None)?,
+                AdminAction::SetPaused(state) => self.apply_pause(by, state, 0, None),
+                AdminAction::SetBlacklist { account, state } => {
+                    self.apply_blacklist(by, account, state, 0)
+                }
+                AdminAction::SetFeeBps(bps) => {
+                    if u128::from(bps) > BPS_DENOMINATOR {
+                        return Err(Error::InvalidBps);
+                    }
+                    self.fee_bps.set(&bps);
+                }
+                AdminAction::SetTreasury(treasury) => self.treasury.set(&treasury),
+                AdminAction::SetTimelockDelay(delay) => self.timelock_delay.set(&delay),
+                AdminAction::UpgradeCode(code_hash) => {
+                    if self.upgrades_renounced() {
+                        return Err(Error::UpgradesRenounced);
+                    }
+                    self.env()
+                        .set_code_hash(&code_hash)
+                        .map_err(|_| Error::UpgradeFailed)?;
+                    self.env().emit_event(CodeUpgraded { code_hash, by });
+                }
+            }
+            Ok(())
+        }
+
+        /// Mint `total` into custody for an airdrop to the leaves under `root`,
+        /// claimable until `deadline` (owner or grantee). Any previous airdrop
+        /// must have been fully claimed or swept.
+        #[ink(message)]
+        pub fn start_merkle_airdrop(
+            &mut self,
+            root: Hash,
+            total: TokenBalance,
+            deadline: Timestamp,
+        ) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("start_merkle_airdrop"))?;
+            if self.merkle_airdrop().is_some_and(|airdrop| airdrop.remaining > 0) {
+                return Err(Error::AirdropActive);
+            }
+            if deadline <= self.env().block_timestamp() {
+                return Err(Error::Expired);
+            }
+            self.mint_internal(self.env().account_id(), total, None)?;
+            self.merkle_airdrop.set(&Some(MerkleAirdrop {
+                root,
+                deadline,
+                remaining: total,
+            }));
+            self.env().emit_event(MerkleAirdropStarted {
+                root,
+                total,
+                deadline,
+            });
+            Ok(())
+        }
+
+        /// Claim the caller's `amount` leaf of the current merkle airdrop
+        #[ink(message)]
+        pub fn claim_airdrop(&mut self, amount: TokenBalance, proof: Vec<Hash>) -> Result<()> {
+            let caller = self.env().caller();
+            let mut airdrop = self.merkle_airdrop().ok_or(Error::AirdropNotActive)?;
+            if self.env().block_timestamp() > airdrop.deadline {
+                return Err(Error::AirdropNotActive);
+            }
+            let leaf = Hash::from(self.env().hash_encoded::<Blake2x256, _>(&(caller, amount)));
+            if self.merkle_airdrop_claimed.contains((airdrop.root, leaf)) {
+                return Err(Error::AirdropClaimed);
+            }
+            if Self::merkle_root(leaf, &proof) != airdrop.root || amount > airdrop.remaining {
+                return Err(Error::InvalidProof);
+            }
+            self.merkle_airdrop_claimed.insert((airdrop.root, leaf), &());
+            airdrop.remaining = airdrop.remaining.saturating_sub(amount);
+            self.merkle_airdrop.set(&Some(airdrop));
+
+            let custody = self.env().account_id();
+            self.debit(custody, amount)?;
+            self.deliver(custody, caller, amount)?;
+            self.env().emit_event(MerkleAirdropClaimed {
+                account: caller,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Root reached by hashing `leaf` up along `proof`, each pair in
+        /// sorted order
+        fn merkle_root(leaf: Hash, proof: &[Hash]) -> Hash {
+            proof.iter().fold(leaf, |node, sibling| {
+                let (first, second) = if node.as_ref() <= sibling.as_ref() {
+                    (node, *sibling)
+                } else {
+                    (*sibling, node)
+                };
+                let mut pair = [0u8; 64];
+                pair[..32].copy_from_slice(first.as_ref());
+                pair[32..].copy_from_slice(second.as_ref());
+                let mut parent = [0u8; 32];
+                ink::env::hash_bytes::<Blake2x256>(&pair, &mut parent);
+                Hash::from(parent)
+            })
+        }
+
+        /// Send the unclaimed rest of an expired merkle airdrop to `to`
+        /// (owner or grantee)
+        #[ink(message)]
+        pub fn sweep_airdrop(&mut self, to: AccountId) -> Result<TokenBalance> {
+            self.ensure_permitted(ink::selector_bytes!("sweep_airdrop"))?;
+            let mut airdrop = self.merkle_airdrop().ok_or(Error::AirdropNotActive)?;
+            if self.env().block_timestamp() <= airdrop.deadline {
+                return Err(Error::AirdropActive);
+            }
+            let amount = airdrop.remaining;
+            airdrop.remaining = 0;
+            self.merkle_airdrop.set(&Some(airdrop));
+            if amount > 0 {
+                let custody = self.env().account_id();
+                self.debit(custody, amount)?;
+                self.deliver(custody, to, amount)?;
+                self.note_treasury_inflow(custody, to, amount)?;
+            }
+            self.env().emit_event(MerkleAirdropSwept { to, amount });
+            Ok(amount)
+        }
+
+        /// Current merkle airdrop
+        #[ink(message)]
+        pub fn merkle_airdrop(&self) -> Option<MerkleAirdrop> {
+            self.merkle_airdrop.get().flatten()
+        }
+
+        /// Whether `account`'s `amount` leaf of the current airdrop was claimed
+        #[ink(message)]
+        pub fn is_airdrop_claimed(&self, account: AccountId, amount: TokenBalance) -> bool {
+            let Some(airdrop) = self.merkle_airdrop() else {
+                return false;
+            };
+            let leaf = Hash::from(self.env().hash_encoded::<Blake2x256, _>(&(account, amount)));
+            self.merkle_airdrop_claimed.contains((airdrop.root, leaf))
+        }
+
+        /// Mint to many recipients at once (owner or grantee). Emits a single
+        /// `Airdropped` event for the whole batch instead of one per recipient.
+        #[ink(message)]
+        pub fn airdrop(
+            &mut self,
+            recipients: Vec<AccountId>,
+            amounts: Vec<TokenBalance>,
+        ) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("airdrop"))?;
+            if recipients.len() != amounts.len() {
+                return Err(Error::LengthMismatch);
+            }
+            let total = amounts
+                .iter()
+                .try_fold(0, |sum: TokenBalance, amount| sum.checked_add(*amount))
+                .ok_or(Error::Overflow)?;
+            let new_total = self.increase_supply(total)?;
+            for (to, amount) in recipients.iter().zip(amounts) {
+                self.call_transfer_hooks(BEFORE_TRANSFER, None, Some(*to), amount)?;
+                self.credit(*to, amount)?;
+                self.call_transfer_hooks(AFTER_TRANSFER, None, Some(*to), amount)?;
+            }
+            self.env().emit_event(Airdropped {
+                count: recipients.len() as u32,
+                total,
+            });
+            self.env().emit_event(SupplyIncreased {
+                by: total,
+                new_total,
+            });
+            Ok(())
+        }
+
+        /// Mint to many recipients at once (owner or grantee), with a `Mint`
+        /// event per recipient. The supply cap applies to the batch total.
+        #[ink(message)]
+        pub fn mint_many(
+            &mut self,
+            recipients: Vec<AccountId>,
+            amounts: Vec<TokenBalance>,
+        ) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("mint_many"))?;
+            if recipients.len() != amounts.len() {
+                return Err(Error::LengthMismatch);
+            }
+            let total = amounts
+                .iter()
+                .try_fold(0, |sum: TokenBalance, amount| sum.checked_add(*amount))
+                .ok_or(Error::Overflow)?;
+            let new_total = self.increase_supply(total)?;
+            for (to, amount) in recipients.into_iter().zip(amounts) {
+                self.call_transfer_hooks(BEFORE_TRANSFER, None, Some(to), amount)?;
+                self.credit(to, amount)?;
+                self.env().emit_event(Mint {
+                    to,
+                    amount,
+                    reference: None,
+                });
+                self.call_transfer_hooks(AFTER_TRANSFER, None, Some(to), amount)?;
+            }
+            self.env().emit_event(SupplyIncreased {
+                by: total,
+                new_total,
+            });
+            Ok(())
+        }
+
+        /// Escrow `deposit` of the caller's tokens, streamed linearly to `to`
+        /// from `start` until `stop`. Returns the stream id.
+        #[ink(message)]
+        pub fn create_stream(
+            &mut self,
+            to: AccountId,
+            deposit: TokenBalance,
+            start: Timestamp,
+            stop: Timestamp,
+        ) -> Result<u32> {
+            if stop <= start {
+                return Err(Error::InvalidStream);
+            }
+            let caller = self.env().caller();
+            self.can_transfer(&caller, &to, deposit)?;
+            self.debit(caller, deposit)?;
+            self.record_spending(caller, deposit);
+            self.deliver(caller, self.env().account_id(), deposit)?;
+
+            let stream_id = self.stream_count.get_or_default();
+            self.streams.insert(
+                stream_id,
+                &Stream {
+                    sender: caller,
+                    recipient: to,
+                    deposit,
+                    start,
+                    stop,
+                    withdrawn: 0,
+                },
+            );
+            let next_id = stream_id.checked_add(1).ok_or(Error::Overflow)?;
+            self.stream_count.set(&next_id);
+            self.env().emit_event(StreamCreated {
+                stream_id,
+                sender: caller,
+                recipient: to,
+                deposit,
+                start,
+                stop,
+            });
+            Ok(stream_id)
+        }
+
+        /// Withdraw up to the streamed, not yet withdrawn amount (recipient)
+        #[ink(message)]
+        pub fn withdraw_from_stream(
+            &mut self,
+            stream_id: u32,
+            amount: TokenBalance,
+        ) -> Result<()> {
+            let mut stream = self.streams.get(stream_id).ok_or(Error::UnknownStream)?;
+            if self.env().caller() != stream.recipient {
+                return Err(Error::NotStreamParty);
+            }
+            if amount > self.withdrawable_from_stream(stream_id) {
+                return Err(Error::InsufficientBalance);
+            }
+            stream.withdrawn = stream.withdrawn.saturating_add(amount);
+            if stream.withdrawn == stream.deposit {
+                self.streams.remove(stream_id);
+            } else {
+                self.streams.insert(stream_id, &stream);
+            }
+            let custody = self.env().account_id();
+            self.debit(custody, amount)?;
+            self.deliver(custody, stream.recipient, amount)?;
+            self.env().emit_event(StreamWithdrawn { stream_id, amount });
+            Ok(())
+        }
+
+        /// End a stream (sender or recipient): the recipient gets what has
+        /// streamed and is not withdrawn yet, the sender the rest
+        #[ink(message)]
+        pub fn cancel_stream(&mut self, stream_id: u32) -> Result<()> {
+            let stream = self.streams.get(stream_id).ok_or(Error::UnknownStream)?;
+            let caller = self.env().caller();
+            if caller != stream.sender && caller != stream.recipient {
+                return Err(Error::NotStreamParty);
+            }
+            self.streams.remove(stream_id);
+            let streamed = stream.streamed_at(self.env().block_timestamp());
+            let recipient_amount = streamed.saturating_sub(stream.withdrawn);
+            let sender_amount = stream.deposit.saturating_sub(streamed);
+            let custody = self.env().account_id();
+            self.debit(custody, recipient_amount.saturating_add(sender_amount))?;
+            self.deliver(custody, stream.recipient, recipient_amount)?;
+            self.deliver(custody, stream.sender, sender_amount)?;
+            self.env().emit_event(StreamCancelled {
+                stream_id,
+                recipient_amount,
+                sender_amount,
+            });
+            Ok(())
+        }
+
+        /// Amount the recipient can withdraw from a stream now
+        #[ink(message)]
+        pub fn withdrawable_from_stream(&self, stream_id: u32) -> TokenBalance {
+            self.streams
+                .get(stream_id)
+                .map(|stream| {
+                    stream
+                        .streamed_at(self.env().block_timestamp())
+                        .saturating_sub(stream.withdrawn)
+                })
+                .unwrap_or(0)
+        }
+
+        /// Stream details
+        #[ink(message)]
+        pub fn stream(&self, stream_id: u32) -> Option<Stream> {
+            self.streams.get(stream_id)
+        }
+
+        /// Lock `amount` of the caller's tokens in custody for `beneficiary`.
+        /// The arbiter or the caller can release them; the arbiter can refund
+        /// them at any time and the caller once `timeout` ms have passed.
+        /// Returns the escrow id.
+        #[ink(message, payable)]
+        pub fn create_escrow(
+            &mut self,
+            beneficiary: AccountId,
+            amount: TokenBalance,
+            arbiter: AccountId,
+            timeout: Timestamp,
+        ) -> Result<u32> {
+            self.collect_native_fee()?;
+            let caller = self.env().caller();
+            self.can_transfer(&caller, &beneficiary, amount)?;
+            self.debit(caller, amount)?;
+            self.record_spending(caller, amount);
+            self.deliver(caller, self.env().account_id(), amount)?;
+
+            let escrow_id = self.escrow_count.get_or_default();
+            let refundable_at = self.env().block_timestamp().saturating_add(timeout);
+            self.escrows.insert(
+                escrow_id,
+                &Escrow {
+                    sender: caller,
+                    beneficiary,
+                    arbiter,
+                    amount,
+                    refundable_at,
+                },
+            );
+            let next_id = escrow_id.checked_add(1).ok_or(Error::Overflow)?;
+            self.escrow_count.set(&next_id);
+            self.env().emit_event(EscrowCreated {
+                escrow_id,
+                sender: caller,
+                beneficiary,
+                arbiter,
+                amount,
+                refundable_at,
+            });
+            Ok(escrow_id)
+        }
+
+        /// Pay an escrow out to its beneficiary (arbiter or sender)
+        #[ink(message)]
+        pub fn release_escrow(&mut self, escrow_id: u32) -> Result<()> {
+            let caller = self.env().caller();
+            let escrow = self.escrows.get(escrow_id).ok_or(Error::UnknownEscrow)?;
+            if caller != escrow.arbiter && caller != escrow.sender {
+                return Err(Error::NotEscrowParty);
+            }
+            self.escrows.remove(escrow_id);
+            let custody = self.env().account_id();
+            self.can_transfer(&custody, &escrow.beneficiary, escrow.amount)?;
+            self.transfer_internal(custody, escrow.beneficiary, escrow.amount)?;
+            self.env().emit_event(EscrowSettled {
+                escrow_id,
+                released: true,
+            });
+            Ok(())
+        }
+
+        /// Return an escrow to its sender (arbiter any time, sender after
+        /// the timeout)
+        #[ink(message)]
+        pub fn refund_escrow(&mut self, escrow_id: u32) -> Result<()> {
+            let caller = self.env().caller();
+            let escrow = self.escrows.get(escrow_id).ok_or(Error::UnknownEscrow)?;
+            if caller != escrow.arbiter {
+                if caller != escrow.sender {
+                    return Err(Error::NotEscrowParty);
+                }
+                if self.env().block_timestamp() < escrow.refundable_at {
+                    return Err(Error::EscrowActive);
+                }
+            }
+            self.escrows.remove(escrow_id);
+            let custody = self.env().account_id();
+            self.debit(custody, escrow.amount)?;
+            self.deliver(custody, escrow.sender, escrow.amount)?;
+            self.env().emit_event(EscrowSettled {
+                escrow_id,
+                released: false,
+            });
+            Ok(())
+        }
+
+        /// Escrow details
+        #[ink(message)]
+        pub fn escrow(&self, escrow_id: u32) -> Option<Escrow> {
+            self.escrows.get(escrow_id)
+        }
+
+        /// Replace the contract code, keeping storage (owner or grantee).
+        /// Run `migrate` afterwards if the new code changes the layout.
+        #[ink(message)]
+        pub fn upgrade_code(&mut self, code_hash: Hash) -> Result<()> {
+            let caller = self.ensure_permitted(ink::selector_bytes!("upgrade_code"))?;
+            self.apply_action(caller, AdminAction::UpgradeCode(code_hash))
+        }
+
+        /// Permanently disable `upgrade_code` (admin only)
+        #[ink(message)]
+        pub fn renounce_upgrades(&mut self) -> Result<()> {
+            let caller = self.ensure_admin()?;
+            self.upgrades_renounced.set(&true);
+            self.env().emit_event(UpgradesRenounced { by: caller });
+            Ok(())
+        }
 
-    #[ink(storage)]
-    pub struct SimpleToken {
-        balances: Mapping<AccountId, u128>,
-        allowances: Mapping<(AccountId, AccountId), u128>, // (owner, spender) → allowance
-        owner: AccountId,
-        paused: bool,
-        blacklist: Mapping<AccountId, bool>,
-    }
+        /// Whether code upgrades were permanently disabled
+        #[ink(message)]
+        pub fn upgrades_renounced(&self) -> bool {
+            self.upgrades_renounced.get_or_default()
+        }
 
-    // Events
-    #[ink(event)]
-    pub struct Mint {
-        #[ink(topic)]
-        to: AccountId,
-        amount: u128,
-    }
+        /// Set the flash loan fee in basis points (owner or grantee)
+        #[ink(message)]
+        pub fn set_flash_fee_bps(&mut self, bps: u16) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_flash_fee_bps"))?;
+            if u128::from(bps) > BPS_DENOMINATOR {
+                return Err(Error::InvalidBps);
+            }
+            self.flash_fee_bps.set(&bps);
+            Ok(())
+        }
 
-    #[ink(event)]
-    pub struct Burn {
-        #[ink(topic)]
-        from: AccountId,
-        amount: u128,
-    }
+        /// Fee charged on a flash loan of `amount`
+        #[ink(message)]
+        pub fn flash_fee(&self, amount: TokenBalance) -> TokenBalance {
+            let bps = u128::from(self.flash_fee_bps.get_or_default());
+            mul_div(amount, bps, BPS_DENOMINATOR).unwrap_or(0)
+        }
 
-    #[ink(event)]
-    pub struct Transfer {
-        #[ink(topic)]
-        from: AccountId,
-        #[ink(topic)]
-        to: AccountId,
-        amount: u128,
-    }
+        /// Mint `amount` to `receiver` and call its
+        /// `FlashBorrower::on_flash_loan(initiator, amount, fee, data)`, which
+        /// must return `Ok(())` and leave an allowance of `amount + fee` to
+        /// this contract. The amount is then burned back and the fee goes to
+        /// the treasury (burned without one); anything short reverts the loan.
+        #[ink(message)]
+        pub fn flash_loan(
+            &mut self,
+            receiver: AccountId,
+            amount: TokenBalance,
+            data: Vec<u8>,
+        ) -> Result<()> {
+            let initiator = self.env().caller();
+            let fee = self.flash_fee(amount);
+            self.mint_internal(receiver, amount, None)?;
 
-    #[ink(event)]
-    pub struct Approval {
-        #[ink(topic)]
-        owner: AccountId,
-        #[ink(topic)]
-        spender: AccountId,
-        amount: u128,
-    }
+            let repaid = build_call::<Environment>()
+                .call(receiver)
+                .call_flags(ink::env::CallFlags::ALLOW_REENTRY)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ON_FLASH_LOAN))
+                        .push_arg(initiator)
+                        .push_arg(amount)
+                        .push_arg(fee)
+                        .push_arg(data),
+                )
+                .returns::<core::result::Result<(), PSP22Error>>()
+                .try_invoke();
+            if !matches!(repaid, Ok(Ok(Ok(())))) {
+                return Err(Error::FlashLoanRejected);
+            }
 
-    impl Default for SimpleToken {
-        fn default() -> Self {
-            Self::new()
+            let repayment = amount.checked_add(fee).ok_or(Error::Overflow)?;
+            let this = self.env().account_id();
+            let allowance = self.live_allowance(receiver, this)?;
+            if allowance < repayment {
+                return Err(Error::AllowanceTooLow);
+            }
+            self.allowances.insert((receiver, this), &(allowance.saturating_sub(repayment)));
+            self.debit(receiver, repayment)?;
+            self.reduce_supply(receiver, amount)?;
+            if fee > 0 {
+                match self.treasury() {
+                    Some(treasury) => {
+                        self.deliver(receiver, treasury, fee)?;
+                        self.note_treasury_inflow(receiver, treasury, fee)?;
+                    }
+                    None => self.reduce_supply(receiver, fee)?,
+                }
+            }
+            self.env().emit_event(FlashLoan {
+                receiver,
+                initiator,
+                amount,
+                fee,
+            });
+            Ok(())
         }
-    }
 
-    impl SimpleToken {
-        /// Constructor
-        #[ink(constructor)]
-        pub fn new() -> Self {
-            let caller = Self::env().caller();
-            Self {
-                balances: Mapping::default(),
-                allowances: Mapping::default(),
-                owner: caller,
-                paused: false,
-                blacklist: Mapping::default(),
+        /// Send PSP22 tokens stuck at this contract's address to `to`
+        /// (owner or grantee)
+        #[ink(message)]
+        pub fn rescue_psp22(
+            &mut self,
+            token: AccountId,
+            to: AccountId,
+            amount: u128,
+        ) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("rescue_psp22"))?;
+            // our own balance here is custody for escrows, streams and vesting
+            if token == self.env().account_id() {
+                return Err(Error::CannotRescueSelf);
             }
+            let sent = build_call::<Environment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(PSP22_TRANSFER))
+                        .push_arg(to)
+                        .push_arg(amount)
+                        .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<core::result::Result<(), PSP22Error>>()
+                .try_invoke();
+            if !matches!(sent, Ok(Ok(Ok(())))) {
+                return Err(Error::RescueFailed);
+            }
+            self.env().emit_event(Rescued {
+                token: Some(token),
+                to,
+                amount,
+            });
+            Ok(())
         }
 
-        /// Internal check for pause/blacklist
-        fn can_transfer(&self, from: &AccountId, to: &AccountId) -> Result<(), String> {
-            if self.paused {
-                return Err("Transfers are paused".into());
+        /// Send native currency stuck at this contract's address to `to`,
+        /// never touching the redemption reserve or unswept sale proceeds
+        /// (owner or grantee)
+        #[ink(message)]
+        pub fn rescue_native(&mut self, to: AccountId, amount: Balance) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("rescue_native"))?;
+            if self.spare_native() < amount {
+                return Err(Error::InsufficientReserve);
             }
-            if self.blacklist.get(from).unwrap_or(false) {
-                return Err("Sender is blacklisted".into());
+            self.env()
+                .transfer(to, amount)
+                .map_err(|_| Error::NativeTransferFailed)?;
+            self.env().emit_event(Rescued {
+                token: None,
+                to,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Set how many tokens `bridge` may have minted and not yet burned
+        /// (owner or grantee)
+        #[ink(message)]
+        pub fn set_bridge_mint_cap(&mut self, bridge: AccountId, cap: TokenBalance) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_bridge_mint_cap"))?;
+            self.bridge_mint_caps.insert(bridge, &cap);
+            Ok(())
+        }
+
+        /// Mint cap of a bridge
+        #[ink(message)]
+        pub fn bridge_mint_cap(&self, bridge: AccountId) -> TokenBalance {
+            self.bridge_mint_caps.get(bridge).unwrap_or(0)
+        }
+
+        /// Tokens a bridge has minted and not yet burned
+        #[ink(message)]
+        pub fn bridge_outstanding(&self, bridge: AccountId) -> TokenBalance {
+            self.bridge_outstanding.get(bridge).unwrap_or(0)
+        }
+
+        /// Re-issue tokens locked on another chain (bridge role only)
+        #[ink(message)]
+        pub fn bridge_mint(&mut self, to: AccountId, amount: TokenBalance) -> Result<()> {
+            let bridge = self.env().caller();
+            if !self.has_role(BRIDGE_ROLE, bridge) {
+                return Err(Error::NotBridge);
             }
-            if self.blacklist.get(to).unwrap_or(false) {
-                return Err("Recipient is blacklisted".into());
+            let outstanding = self
+                .bridge_outstanding(bridge)
+                .checked_add(amount)
+                .ok_or(Error::Overflow)?;
+            if outstanding > self.bridge_mint_cap(bridge) {
+                return Err(Error::BridgeCapExceeded);
             }
+            self.mint_internal(to, amount, None)?;
+            self.bridge_outstanding.insert(bridge, &outstanding);
+            self.env().emit_event(BridgeMinted { bridge, to, amount });
             Ok(())
         }
 
-        /// Mint tokens (only owner)
+        /// Burn tokens leaving for another chain (bridge role only). Burning
+        /// from another account spends the bridge's allowance on it.
         #[ink(message)]
-        pub fn mint(&mut self, to: AccountId, amount: u128) -> Result<(), String> {
-            let caller = self.env().caller();
-            if caller != self.owner {
-                return Err("Only the owner can mint tokens".into());
+        pub fn bridge_burn(&mut self, from: AccountId, amount: TokenBalance) -> Result<()> {
+            let bridge = self.env().caller();
+            if !self.has_role(BRIDGE_ROLE, bridge) {
+                return Err(Error::NotBridge);
+            }
+            if from == bridge {
+                self.burn_internal(from, amount)?;
+            } else {
+                self.burn_from(from, amount)?;
             }
-            let current = self.balances.get(to).unwrap_or(0);
-            let new_balance = current.saturating_add(amount);
-            self.balances.insert(to, &new_balance);
-            self.env().emit_event(Mint { to, amount });
+            let outstanding = self.bridge_outstanding(bridge).saturating_sub(amount);
+            self.bridge_outstanding.insert(bridge, &outstanding);
+            self.env().emit_event(BridgeBurned {
+                bridge,
+                from,
+                amount,
+            });
             Ok(())
         }
 
-        /// Burn own tokens
+        /// Set (or remove with `None`) the route to a parachain (owner or grantee)
         #[ink(message)]
-        pub fn burn(&mut self, amount: u128) -> Result<(), String> {
-            let caller = self.env().caller();
-            let balance = self.balances.get(caller).unwrap_or(0);
-            if balance < amount {
-                return Err("Not enough balance to burn".into());
+        pub fn set_cross_chain_route(
+            &mut self,
+            dest_parachain: u32,
+            route: Option<CrossChainRoute>,
+        ) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_cross_chain_route"))?;
+            match route {
+                Some(route) => {
+                    self.cross_chain_routes.insert(dest_parachain, &route);
+                }
+                None => self.cross_chain_routes.remove(dest_parachain),
             }
-            let updated = balance.saturating_sub(amount);
-            self.balances.insert(caller, &updated);
-            self.env().emit_event(Burn { from: caller, amount });
             Ok(())
         }
 
-        /// Read balance
+        /// Route to a parachain
         #[ink(message)]
-        pub fn balance_of(&self, owner: AccountId) -> u128 {
-            self.balances.get(owner).unwrap_or(0)
+        pub fn cross_chain_route(&self, dest_parachain: u32) -> Option<CrossChainRoute> {
+            self.cross_chain_routes.get(dest_parachain)
         }
 
-        /// Transfer
+        /// Burn `amount` of the caller's tokens here and send an XCM message
+        /// crediting `beneficiary` on `dest_parachain`. Returns the transfer id.
+        #[ink(message)]
+        pub fn transfer_cross_chain(
+            &mut self,
+            dest_parachain: u32,
+            beneficiary: AccountId,
+            amount: TokenBalance,
+        ) -> Result<u64> {
+            use ink::xcm::prelude::*;
+
+            let route = self
+                .cross_chain_routes
+                .get(dest_parachain)
+                .ok_or(Error::UnknownRoute)?;
+            let caller = self.env().caller();
+            self.check_sender(&caller, amount)?;
+            self.burn_internal(caller, amount)?;
+
+            let transfer_id = self.cross_chain_transfer_count.get_or_default();
+            let next_id = transfer_id.checked_add(1).ok_or(Error::Overflow)?;
+            self.cross_chain_transfer_count.set(&next_id);
+
+            let mut call = route.remote_call;
+            ink::scale::Encode::encode_to(&(transfer_id, beneficiary, amount), &mut call);
+            let dest = VersionedLocation::V4(Location::new(1, [Parachain(dest_parachain)]));
+            let message: Xcm<()> = Xcm(Vec::from([
+                UnpaidExecution {
+                    weight_limit: Unlimited,
+                    check_origin: None,
+                },
+                Transact {
+                    origin_kind: OriginKind::SovereignAccount,
+                    require_weight_at_most: Weight::from_parts(route.ref_time, route.proof_size),
+                    call: call.into(),
+                },
+            ]));
+            self.env()
+                .xcm_send(&dest, &VersionedXcm::V4(message))
+                .map_err(|_| Error::XcmSendFailed)?;
+
+            self.env().emit_event(CrossChainTransfer {
+                transfer_id,
+                from: caller,
+                dest_parachain,
+                beneficiary,
+                amount,
+            });
+            Ok(transfer_id)
+        }
+
+        /// Set up a token sale (owner or grantee). Replaces a finalized sale,
+        /// or one that has not received contributions yet.
         #[ink(message)]
-        pub fn transfer(&mut self, to: AccountId, amount: u128) -> Result<(), String> {
+        pub fn configure_sale(
+            &mut self,
+            rate: u128,
+            start: Timestamp,
+            end: Timestamp,
+            account_cap: Balance,
+            hard_cap: Balance,
+            vesting_duration: Timestamp,
+        ) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("configure_sale"))?;
+            if end <= start {
+                return Err(Error::InvalidSaleWindow);
+            }
+            let mut sale_id = self.sale_id.get_or_default();
+            match self.sale() {
+                Some(sale) if !sale.finalized && sale.raised > 0 => {
+                    return Err(Error::SaleActive);
+                }
+                Some(sale) if sale.finalized => {
+                    sale_id = sale_id.checked_add(1).ok_or(Error::Overflow)?;
+                    self.sale_id.set(&sale_id);
+                }
+                _ => {}
+            }
+            self.sale.set(&Some(Sale {
+                rate,
+                start,
+                end,
+                account_cap,
+                hard_cap,
+                vesting_duration,
+                raised: 0,
+                finalized: false,
+            }));
+            self.env().emit_event(SaleConfigured {
+                sale_id,
+                rate,
+                start,
+                end,
+                hard_cap,
+            });
+            Ok(())
+        }
+
+        /// Buy tokens with the transferred native value while the sale is
+        /// open. Returns the amount of tokens bought.
+        #[ink(message, payable)]
+        pub fn contribute(&mut self) -> Result<TokenBalance> {
             let caller = self.env().caller();
-            self.can_transfer(&caller, &to)?;
+            let value = self.env().transferred_value();
+            let now = self.env().block_timestamp();
+            let mut sale = self.sale().ok_or(Error::SaleNotActive)?;
+            if sale.finalized || now < sale.start || now >= sale.end || value == 0 {
+                return Err(Error::SaleNotActive);
+            }
+            let sale_id = self.sale_id.get_or_default();
+            let contributed = self
+                .contribution_of(caller)
+                .checked_add(value)
+                .ok_or(Error::Overflow)?;
+            let raised = sale.raised.checked_add(value).ok_or(Error::Overflow)?;
+            if contributed > sale.account_cap || raised > sale.hard_cap {
+                return Err(Error::SaleCapExceeded);
+            }
+            let tokens = mul_div(value, sale.rate, SALE_RATE_SCALE).ok_or(Error::Overflow)?;
+
+            if sale.vesting_duration == 0 {
+                self.mint_internal(caller, tokens, None)?;
+            } else {
+                let mut schedule = match self.vesting.get(caller) {
+                    None => VestingSchedule {
+                        total: 0,
+                        released: 0,
+                        start: sale.end,
+                        cliff: 0,
+                        duration: sale.vesting_duration,
+                        revocable: false,
+                        revoked: false,
+                    },
+                    // top up the schedule of an earlier contribution to this sale
+                    Some(schedule)
+                        if !schedule.revocable
+                            && schedule.start == sale.end
+                            && schedule.cliff == 0
+                            && schedule.duration == sale.vesting_duration =>
+                    {
+                        schedule
+                    }
+                    Some(_) => return Err(Error::VestingExists),
+                };
+                self.mint_internal(self.env().account_id(), tokens, None)?;
+                schedule.total = schedule.total.checked_add(tokens).ok_or(Error::Overflow)?;
+                self.vesting.insert(caller, &schedule);
+            }
+
+            sale.raised = raised;
+            self.sale.set(&Some(sale));
+            self.sale_contributions.insert((sale_id, caller), &contributed);
+            self.env().emit_event(Contributed {
+                sale_id,
+                account: caller,
+                native_amount: value,
+                tokens,
+            });
+            Ok(tokens)
+        }
 
-            let from_balance = self.balances.get(caller).unwrap_or(0);
-            if from_balance < amount {
-                return Err("Not enough balance".into());
+        /// Close the sale once it has ended or reached its hard cap and send
+        /// the proceeds to the caller (owner or grantee)
+        #[ink(message)]
+        pub fn finalize_sale(&mut self) -> Result<Balance> {
+            let caller = self.ensure_permitted(ink::selector_bytes!("finalize_sale"))?;
+            let mut sale = self.sale().ok_or(Error::SaleNotActive)?;
+            if sale.finalized {
+                return Err(Error::SaleNotActive);
             }
+            if self.env().block_timestamp() < sale.end && sale.raised < sale.hard_cap {
+                return Err(Error::SaleActive);
+            }
+            sale.finalized = true;
+            let raised = sale.raised;
+            self.sale.set(&Some(sale));
+            self.env()
+                .transfer(caller, raised)
+                .map_err(|_| Error::NativeTransferFailed)?;
+            self.env().emit_event(SaleFinalized {
+                sale_id: self.sale_id.get_or_default(),
+                raised,
+            });
+            Ok(raised)
+        }
 
-            let updated_from = from_balance.saturating_sub(amount);
-            self.balances.insert(caller, &updated_from);
+        /// Current (or last) sale
+        #[ink(message)]
+        pub fn sale(&self) -> Option<Sale> {
+            self.sale.get().flatten()
+        }
 
-            let to_balance = self.balances.get(to).unwrap_or(0);
-            let updated_to = to_balance.saturating_add(amount);
-            self.balances.insert(to, &updated_to);
+        /// Native currency `account` contributed to the current sale
+        #[ink(message)]
+        pub fn contribution_of(&self, account: AccountId) -> Balance {
+            self.sale_contributions
+                .get((self.sale_id.get_or_default(), account))
+                .unwrap_or(0)
+        }
 
-            self.env().emit_event(Transfer { from: caller, to, amount });
+        /// Switch the testnet faucet on with `config`, or off with `None`
+        /// (owner or grantee)
+        #[ink(message)]
+        pub fn set_faucet(&mut self, config: Option<FaucetConfig>) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_faucet"))?;
+            self.faucet.set(&config);
             Ok(())
         }
 
-        /// Approve spender
+        /// Faucet settings, `None` while the faucet is off
+        #[ink(message)]
+        pub fn faucet(&self) -> Option<FaucetConfig> {
+            self.faucet.get().flatten()
+        }
+
+        /// Mint the faucet amount to the caller, at most once per cooldown
         #[ink(message)]
-        pub fn approve(&mut self, spender: AccountId, amount: u128) -> Result<(), String> {
+        pub fn faucet_claim(&mut self) -> Result<()> {
+            let config = self.faucet().ok_or(Error::FaucetDisabled)?;
             let caller = self.env().caller();
-            self.allowances.insert((caller, spender), &amount);
-            self.env().emit_event(Approval {
-                owner: caller,
-                spender,
-                amount,
+            let now = self.env().block_timestamp();
+            if self.faucet_next_claim(caller).is_some_and(|next| now < next) {
+                return Err(Error::FaucetCooldown);
+            }
+            self.faucet_claims.insert(caller, &now);
+            self.mint_internal(caller, config.amount, None)?;
+            self.env().emit_event(FaucetClaimed {
+                account: caller,
+                amount: config.amount,
             });
             Ok(())
         }
 
-        /// Allowance query
+        /// Earliest time `account` may claim from the faucet again, `None` if
+        /// it never claimed
         #[ink(message)]
-        pub fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 {
-            self.allowances.get((owner, spender)).unwrap_or(0)
+        pub fn faucet_next_claim(&self, account: AccountId) -> Option<Timestamp> {
+            let cooldown = self.faucet().map(|config| config.cooldown).unwrap_or(0);
+            self.faucet_claims
+                .get(account)
+                .map(|last| last.saturating_add(cooldown))
         }
 
-        /// Transfer from (using allowance)
+        /// Set the DEX router used by `buyback_and_burn`, `None` to disable
+        /// buybacks (owner or grantee)
         #[ink(message)]
-        pub fn transfer_from(
-            &mut self,
-            from: AccountId,
-            to: AccountId,
-            amount: u128,
-        ) -> Result<(), String> {
-            let caller = self.env().caller();
-            self.can_transfer(&from, &to)?;
+        pub fn set_buyback_router(&mut self, router: Option<AccountId>) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_buyback_router"))?;
+            self.buyback_router.set(&router);
+            Ok(())
+        }
 
-            let allowance = self.allowances.get((from, caller)).unwrap_or(0);
-            if allowance < amount {
-                return Err("Allowance too low".into());
+        /// DEX router used for buybacks
+        #[ink(message)]
+        pub fn buyback_router(&self) -> Option<AccountId> {
+            self.buyback_router.get().flatten()
+        }
+
+        /// Spend up to `max_spend` of the contract's spare native currency on
+        /// tokens through the buyback router and burn everything bought;
+        /// reverts unless at least `min_tokens_out` arrive (owner or grantee)
+        #[ink(message)]
+        pub fn buyback_and_burn(
+            &mut self,
+            max_spend: Balance,
+            min_tokens_out: TokenBalance,
+        ) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("buyback_and_burn"))?;
+            let router = self.buyback_router().ok_or(Error::NoBuybackRouter)?;
+            if self.spare_native() < max_spend {
+                return Err(Error::InsufficientReserve);
             }
+            let this = self.env().account_id();
+            let native_before = self.env().balance();
+            let tokens_before = self.balances.get(this).unwrap_or(0);
 
-            let from_balance = self.balances.get(from).unwrap_or(0);
-            if from_balance < amount {
-                return Err("Not enough balance".into());
+            // the router pays out by calling back into `transfer`
+            let swapped = build_call::<Environment>()
+                .call(router)
+                .call_flags(ink::env::CallFlags::ALLOW_REENTRY)
+                .transferred_value(max_spend)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(SWAP_EXACT_NATIVE_FOR_TOKENS))
+                        .push_arg(this)
+                        .push_arg(min_tokens_out)
+                        .push_arg(this),
+                )
+                .returns::<core::result::Result<(), PSP22Error>>()
+                .try_invoke();
+            if !matches!(swapped, Ok(Ok(Ok(())))) {
+                return Err(Error::BuybackFailed);
             }
 
-            // update balances
-            self.balances.insert(from, &(from_balance.saturating_sub(amount)));
-            let to_balance = self.balances.get(to).unwrap_or(0);
-            self.balances.insert(to, &(to_balance.saturating_add(amount)));
+            // measure rather than trust the router's own accounting
+            let bought = self
+                .balances
+                .get(this)
+                .unwrap_or(0)
+                .saturating_sub(tokens_before);
+            if bought < min_tokens_out {
+                return Err(Error::BuybackSlippage);
+            }
+            let spent = native_before.saturating_sub(self.env().balance());
+            self.debit(this, bought)?;
+            self.reduce_supply(this, bought)?;
+            self.env().emit_event(Buyback {
+                router,
+                spent,
+                burned: bought,
+            });
+            Ok(())
+        }
 
-            // update allowance
-            self.allowances.insert((from, caller), &(allowance.saturating_sub(amount)));
+        /// Tokens held by the contract-managed treasury
+        #[ink(message)]
+        pub fn treasury_balance(&self) -> TokenBalance {
+            self.treasury_held.get_or_default()
+        }
 
-            self.env().emit_event(Transfer { from, to, amount });
+        /// Pay `amount` out of the contract-managed treasury to `to`
+        /// (owner or grantee)
+        #[ink(message)]
+        pub fn treasury_transfer(&mut self, to: AccountId, amount: TokenBalance) -> Result<()> {
+            let caller = self.ensure_permitted(ink::selector_bytes!("treasury_transfer"))?;
+            let held = self.treasury_held.get_or_default();
+            if held < amount {
+                return Err(Error::InsufficientTreasury);
+            }
+            self.treasury_held.set(&held.saturating_sub(amount));
+            let custody = self.env().account_id();
+            self.debit(custody, amount)?;
+            self.deliver(custody, to, amount)?;
+            self.env().emit_event(TreasuryTransfer {
+                to,
+                amount,
+                by: caller,
+            });
             Ok(())
         }
 
-        /// Pause / Unpause (owner only)
+        /// Install an emission schedule, or stop emissions with `None`.
+        /// Emission accrues from the later of `start_block` and now; call
+        /// `mint_emission` first to keep what the old schedule accrued
+        /// (owner or grantee)
         #[ink(message)]
-        pub fn set_paused(&mut self, state: bool) -> Result<(), String> {
-            let caller = self.env().caller();
-            if caller != self.owner {
-                return Err("Only owner can pause/unpause".into());
+        pub fn set_emission_schedule(&mut self, schedule: Option<EmissionSchedule>) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("set_emission_schedule"))?;
+            if let Some(schedule) = &schedule {
+                if schedule.halving_interval == 0 {
+                    return Err(Error::InvalidEmissionSchedule);
+                }
+                let now = self.env().block_number();
+                self.emission_minted_until.set(&schedule.start_block.max(now));
             }
-            self.paused = state;
+            self.emission.set(&schedule);
             Ok(())
         }
 
-        /// Blacklist / Unblacklist (owner only)
+        /// Current emission schedule
         #[ink(message)]
-        pub fn set_blacklist(&mut self, account: AccountId, state: bool) -> Result<(), String> {
-            let caller = self.env().caller();
-            if caller != self.owner {
-                return Err("Only owner can manage blacklist".into());
+        pub fn emission_schedule(&self) -> Option<EmissionSchedule> {
+            self.emission.get().flatten()
+        }
+
+        /// Emission accrued up to the current block and not yet minted
+        #[ink(message)]
+        pub fn pending_emission(&self) -> TokenBalance {
+            let Some(schedule) = self.emission_schedule() else {
+                return 0;
+            };
+            let now = self.env().block_number();
+            let minted_until = self.emission_minted_until.get_or_default();
+            schedule
+                .emitted_until(now)
+                .saturating_sub(schedule.emitted_until(minted_until))
+        }
+
+        /// Mint the accrued emission to the schedule's distributor; anyone
+        /// may call this. Returns the amount minted.
+        #[ink(message)]
+        pub fn mint_emission(&mut self) -> Result<TokenBalance> {
+            let schedule = self.emission_schedule().ok_or(Error::NoEmissionSchedule)?;
+            let amount = self.pending_emission();
+            let now = self.env().block_number();
+            if amount == 0 {
+                return Ok(0);
             }
-            self.blacklist.insert(account, &state);
-            Ok(())
+            self.emission_minted_until.set(&now);
+            self.mint_internal(schedule.distributor, amount, None)?;
+            self.env().emit_event(EmissionMinted {
+                distributor: schedule.distributor,
+                amount,
+                until_block: now,
+            });
+            Ok(amount)
         }
 
-        /// Batch transfers
+        /// Layout hash currently recorded in storage
         #[ink(message)]
-        pub fn batch_transfer(
-            &mut self,
-            recipients: Vec<AccountId>,
-            amounts: Vec<u128>,
-        ) -> Result<(), String> {
-            let caller = self.env().caller();
-            if recipients.len() != amounts.len() {
-                return Err("Mismatched input lengths".into());
+        pub fn storage_layout_hash(&self) -> u64 {
+            self.layout_hash.get().unwrap_or(PREVIOUS_STORAGE_LAYOUT_HASH)
+        }
+
+        /// Storage version currently recorded in storage
+        #[ink(message)]
+        pub fn storage_version(&self) -> u32 {
+            self.storage_version.get_or_default()
+        }
+
+        /// Post-upgrade migration (owner or grantee). Runs every migration
+        /// step from the stored version up to `STORAGE_VERSION`.
+        #[ink(message)]
+        pub fn migrate(&mut self) -> Result<()> {
+            self.ensure_permitted(ink::selector_bytes!("migrate"))?;
+            let from_version = self.storage_version();
+            if from_version >= STORAGE_VERSION {
+                return Err(Error::AlreadyMigrated);
+            }
+            let from_layout = self.storage_layout_hash();
+            for version in from_version..STORAGE_VERSION {
+                self.migrate_step(version)?;
             }
+            self.storage_version.set(&STORAGE_VERSION);
+            self.env().emit_event(Migrated {
+                from_layout,
+                to_layout: STORAGE_LAYOUT_HASH,
+                from_version,
+                to_version: STORAGE_VERSION,
+            });
+            Ok(())
+        }
 
-            for i in 0..recipients.len() {
-                self.transfer(recipients[i], amounts[i])?;
+        /// Bring storage from `version` to `version + 1`
+        fn migrate_step(&mut self, version: u32) -> Result<()> {
+            match version {
+                // 0.1.0: refuse unless the stored layout is exactly the one
+                // this code was built to upgrade from
+                0 => {
+                    if self.storage_layout_hash() != PREVIOUS_STORAGE_LAYOUT_HASH {
+                        return Err(Error::LayoutMismatch);
+                    }
+                    self.layout_hash.set(&STORAGE_LAYOUT_HASH);
+                }
+                _ => return Err(Error::LayoutMismatch),
             }
             Ok(())
         }
     }
+
+    impl PSP22 for SimpleToken {
+        #[ink(message)]
+        fn total_supply(&self) -> u128 {
+            self.total_supply.get_or_default()
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u128 {
+            self.current_balance(&owner)
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 {
+            self.live_allowance(owner, spender).unwrap_or(0)
+        }
+
+        #[ink(message, payable)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            value: u128,
+            _data: Vec<u8>,
+        ) -> core::result::Result<(), PSP22Error> {
+            self.collect_native_fee()?;
+            Ok(self.send(to, value)?)
+        }
+
+        #[ink(message, payable)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: u128,
+            _data: Vec<u8>,
+        ) -> core::result::Result<(), PSP22Error> {
+            self.collect_native_fee()?;
+            Ok(self.transfer_with_allowance(from, to, value)?)
+        }
+
+        #[ink(message)]
+        fn approve(
+            &mut self,
+            spender: AccountId,
+            value: u128,
+        ) -> core::result::Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            Ok(self.approve_until(caller, spender, value, None)?)
+        }
+
+        #[ink(message)]
+        fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: u128,
+        ) -> core::result::Result<(), PSP22Error> {
+            Ok(SimpleToken::increase_allowance(self, spender, delta_value)?)
+        }
+
+        #[ink(message)]
+        fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: u128,
+        ) -> core::result::Result<(), PSP22Error> {
+            Ok(SimpleToken::decrease_allowance(self, spender, delta_value)?)
+        }
+    }
+
+    impl PSP22Metadata for SimpleToken {
+        #[ink(message)]
+        fn token_name(&self) -> Option<String> {
+            self.metadata.get_or_default().name
+        }
+
+        #[ink(message)]
+        fn token_symbol(&self) -> Option<String> {
+            self.metadata.get_or_default().symbol
+        }
+
+        #[ink(message)]
+        fn token_decimals(&self) -> u8 {
+            self.metadata.get_or_default().decimals
+        }
+    }
+
+    impl PSP22Mintable for SimpleToken {
+        /// Mint to the caller (owner or grantee)
+        #[ink(message)]
+        fn mint(&mut self, value: u128) -> core::result::Result<(), PSP22Error> {
+            let caller = self.ensure_permitted(ink::selector_bytes!("PSP22Mintable::mint"))?;
+            Ok(self.mint_internal(caller, value, None)?)
+        }
+    }
+
+    impl PSP22Burnable for SimpleToken {
+        #[ink(message)]
+        fn burn(&mut self, value: u128) -> core::result::Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            Ok(self.burn_internal(caller, value)?)
+        }
+    }
 }
@@ -0,0 +1,107 @@
+//! Wide intermediate arithmetic
+//!
+//! Token amounts are stored as [`TokenBalance`](crate::TokenBalance), but
+//! products such as `amount * rate` can overflow it long before the result
+//! does. [`U256`] holds such intermediates exactly; converting back to a stored
+//! amount is an explicit, checked step.
+
+use core::cmp::Ordering;
+
+/// Unsigned 256-bit integer, just wide enough for `u128 * u128`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct U256 {
+    hi: u128,
+    lo: u128,
+}
+
+impl U256 {
+    pub const ZERO: U256 = U256 { hi: 0, lo: 0 };
+
+    /// Widen a `u128`
+    pub const fn from_u128(value: u128) -> Self {
+        U256 { hi: 0, lo: value }
+    }
+
+    /// Exact product of two `u128`s
+    pub const fn mul(a: u128, b: u128) -> Self {
+        const MASK: u128 = u64::MAX as u128;
+        let (a_hi, a_lo) = (a >> 64, a & MASK);
+        let (b_hi, b_lo) = (b >> 64, b & MASK);
+
+        let lo_lo = a_lo * b_lo;
+        let hi_lo = a_hi * b_lo;
+        let lo_hi = a_lo * b_hi;
+        let hi_hi = a_hi * b_hi;
+
+        let cross = (lo_lo >> 64) + (hi_lo & MASK) + (lo_hi & MASK);
+        U256 {
+            hi: hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (cross >> 64),
+            lo: (cross << 64) | (lo_lo & MASK),
+        }
+    }
+
+    /// Quotient of a division by a `u128`, `None` for a zero divisor
+    pub fn checked_div(self, divisor: u128) -> Option<Self> {
+        if divisor == 0 {
+            return None;
+        }
+        if self.hi == 0 {
+            return Some(U256::from_u128(self.lo / divisor));
+        }
+        // shift-subtract long division, one quotient bit per step
+        let mut quotient = U256::ZERO;
+        let mut remainder: u128 = 0;
+        for bit in (0..256).rev() {
+            let carry = remainder >> 127;
+            remainder = (remainder << 1) | self.bit(bit);
+            if carry == 1 || remainder >= divisor {
+                remainder = remainder.wrapping_sub(divisor);
+                quotient.set_bit(bit);
+            }
+        }
+        Some(quotient)
+    }
+
+    /// Narrow back to `u128`, `None` if the value does not fit
+    pub const fn to_u128(self) -> Option<u128> {
+        if self.hi == 0 {
+            Some(self.lo)
+        } else {
+            None
+        }
+    }
+
+    fn bit(&self, index: u32) -> u128 {
+        if index >= 128 {
+            (self.hi >> (index - 128)) & 1
+        } else {
+            (self.lo >> index) & 1
+        }
+    }
+
+    fn set_bit(&mut self, index: u32) {
+        if index >= 128 {
+            self.hi |= 1 << (index - 128);
+        } else {
+            self.lo |= 1 << index;
+        }
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for U256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.hi, self.lo).cmp(&(other.hi, other.lo))
+    }
+}
+
+/// `a * b / divisor` computed through a 256-bit intermediate, rounding down.
+/// `None` if the divisor is zero or the result does not fit in `u128`.
+pub fn mul_div(a: u128, b: u128, divisor: u128) -> Option<u128> {
+    U256::mul(a, b).checked_div(divisor)?.to_u128()
+}
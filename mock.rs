@@ -0,0 +1,120 @@
+//! In-memory stand-in for the token
+//!
+//! `MockSimpleToken` implements [`SimpleTokenApi`] without a node or the
+//! off-chain contract environment. Balances and allowances live in plain maps,
+//! the caller is set explicitly, and failures can be injected to exercise a
+//! dependent contract's error paths.
+
+use crate::api::SimpleTokenApi;
+use crate::{Error, TokenBalance};
+use ink::primitives::AccountId;
+use std::collections::HashMap;
+
+/// In-memory token with configurable failure injection
+#[derive(Debug, Clone)]
+pub struct MockSimpleToken {
+    caller: AccountId,
+    total_supply: TokenBalance,
+    balances: HashMap<AccountId, TokenBalance>,
+    allowances: HashMap<(AccountId, AccountId), TokenBalance>,
+    fail_with: Option<Error>,
+    fail_next: Option<Error>,
+}
+
+impl MockSimpleToken {
+    /// Empty token; mutating calls act as `caller`
+    pub fn new(caller: AccountId) -> Self {
+        Self {
+            caller,
+            total_supply: 0,
+            balances: HashMap::new(),
+            allowances: HashMap::new(),
+            fail_with: None,
+            fail_next: None,
+        }
+    }
+
+    /// Act as `caller` for the following mutating calls
+    pub fn set_caller(&mut self, caller: AccountId) {
+        self.caller = caller;
+    }
+
+    /// Credit `amount` to `to` and grow the supply
+    pub fn mint(&mut self, to: AccountId, amount: TokenBalance) {
+        *self.balances.entry(to).or_insert(0) += amount;
+        self.total_supply += amount;
+    }
+
+    /// Make every mutating call fail with `error` until cleared with `None`
+    pub fn fail_with(&mut self, error: Option<Error>) {
+        self.fail_with = error;
+    }
+
+    /// Make only the next mutating call fail with `error`
+    pub fn fail_next(&mut self, error: Error) {
+        self.fail_next = Some(error);
+    }
+
+    fn injected_failure(&mut self) -> Result<(), Error> {
+        match self.fail_next.take().or(self.fail_with) {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    fn move_balance(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        amount: TokenBalance,
+    ) -> Result<(), Error> {
+        let balance = self.balance_of(from);
+        if balance < amount {
+            return Err(Error::InsufficientBalance);
+        }
+        self.balances.insert(from, balance - amount);
+        *self.balances.entry(to).or_insert(0) += amount;
+        Ok(())
+    }
+}
+
+impl SimpleTokenApi for MockSimpleToken {
+    fn total_supply(&self) -> TokenBalance {
+        self.total_supply
+    }
+
+    fn balance_of(&self, owner: AccountId) -> TokenBalance {
+        self.balances.get(&owner).copied().unwrap_or(0)
+    }
+
+    fn allowance(&self, owner: AccountId, spender: AccountId) -> TokenBalance {
+        self.allowances.get(&(owner, spender)).copied().unwrap_or(0)
+    }
+
+    fn transfer(&mut self, to: AccountId, amount: TokenBalance) -> Result<(), Error> {
+        self.injected_failure()?;
+        self.move_balance(self.caller, to, amount)
+    }
+
+    fn approve(&mut self, spender: AccountId, amount: TokenBalance) -> Result<(), Error> {
+        self.injected_failure()?;
+        self.allowances.insert((self.caller, spender), amount);
+        Ok(())
+    }
+
+    fn transfer_from(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        amount: TokenBalance,
+    ) -> Result<(), Error> {
+        self.injected_failure()?;
+        let allowance = self.allowance(from, self.caller);
+        if allowance < amount {
+            return Err(Error::AllowanceTooLow);
+        }
+        self.move_balance(from, to, amount)?;
+        self.allowances.insert((from, self.caller), allowance - amount);
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+//! PSP22 fungible token standard
+//!
+//! Trait definitions for PSP22, its Metadata, Mintable and Burnable
+//! extensions and the PSP22Receiver hook. Message selectors are derived from
+//! `Trait::message`, which yields the standard selectors (e.g.
+//! `PSP22::transfer` = `0xdb20f9f5`), so wallets, DEXes and other contracts
+//! can call any conforming token alike.
+
+use ink::prelude::{string::String, vec::Vec};
+use ink::primitives::AccountId;
+
+/// Error type of the standard PSP22 messages
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum PSP22Error {
+    /// Implementation-specific error
+    Custom(String),
+    InsufficientBalance,
+    InsufficientAllowance,
+    ZeroRecipientAddress,
+    ZeroSenderAddress,
+    SafeTransferCheckFailed(String),
+}
+
+/// Error a receiver returns to refuse incoming tokens
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum PSP22ReceiverError {
+    TransferRejected(String),
+}
+
+#[ink::trait_definition]
+pub trait PSP22 {
+    /// Total token supply
+    #[ink(message)]
+    fn total_supply(&self) -> u128;
+
+    /// Balance of `owner`
+    #[ink(message)]
+    fn balance_of(&self, owner: AccountId) -> u128;
+
+    /// Amount `spender` may still withdraw from `owner`
+    #[ink(message)]
+    fn allowance(&self, owner: AccountId, spender: AccountId) -> u128;
+
+    /// Transfer `value` from the caller to `to`
+    #[ink(message, payable)]
+    fn transfer(&mut self, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error>;
+
+    /// Transfer `value` from `from` to `to` using the caller's allowance
+    #[ink(message, payable)]
+    fn transfer_from(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        value: u128,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+
+    /// Set the caller's allowance for `spender` to `value`
+    #[ink(message)]
+    fn approve(&mut self, spender: AccountId, value: u128) -> Result<(), PSP22Error>;
+
+    /// Raise the caller's allowance for `spender` by `delta_value`
+    #[ink(message)]
+    fn increase_allowance(
+        &mut self,
+        spender: AccountId,
+        delta_value: u128,
+    ) -> Result<(), PSP22Error>;
+
+    /// Lower the caller's allowance for `spender` by `delta_value`
+    #[ink(message)]
+    fn decrease_allowance(
+        &mut self,
+        spender: AccountId,
+        delta_value: u128,
+    ) -> Result<(), PSP22Error>;
+}
+
+#[ink::trait_definition]
+pub trait PSP22Metadata {
+    /// Token name
+    #[ink(message)]
+    fn token_name(&self) -> Option<String>;
+
+    /// Token symbol
+    #[ink(message)]
+    fn token_symbol(&self) -> Option<String>;
+
+    /// Number of decimals used to display amounts
+    #[ink(message)]
+    fn token_decimals(&self) -> u8;
+}
+
+#[ink::trait_definition]
+pub trait PSP22Mintable {
+    /// Mint `value` tokens to the caller
+    #[ink(message)]
+    fn mint(&mut self, value: u128) -> Result<(), PSP22Error>;
+}
+
+#[ink::trait_definition]
+pub trait PSP22Burnable {
+    /// Burn `value` of the caller's tokens
+    #[ink(message)]
+    fn burn(&mut self, value: u128) -> Result<(), PSP22Error>;
+}
+
+#[ink::trait_definition]
+pub trait PSP22Receiver {
+    /// Called on a receiving contract before `value` sent by `operator` on
+    /// behalf of `from` is credited to it; an error rejects the transfer
+    #[ink(message)]
+    fn before_received(
+        &mut self,
+        operator: AccountId,
+        from: AccountId,
+        value: u128,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22ReceiverError>;
+}
@@ -0,0 +1,18 @@
+#!/usr/bin/env sh
+# Builds the optimized contract and fails if the Wasm blob exceeds the
+# code-size budget. The default budget stays well under the chain's
+# `MaxCodeLen` (123 KiB on current pallet-contracts runtimes).
+set -eu
+
+MAX_CODE_SIZE="${MAX_CODE_SIZE:-98304}"
+WASM="target/ink/simple_token.wasm"
+
+cargo contract build --release --quiet
+
+size=$(wc -c < "$WASM")
+echo "simple_token.wasm: ${size} bytes (budget ${MAX_CODE_SIZE})"
+
+if [ "$size" -gt "$MAX_CODE_SIZE" ]; then
+    echo "error: contract exceeds code-size budget" >&2
+    exit 1
+fi
@@ -0,0 +1,67 @@
+//! Off-chain test helpers
+//!
+//! Thin wrappers around `ink::env::test` for driving the clock, the caller and
+//! the transferred value in unit tests. Exported so that contracts depending on
+//! SimpleToken can reuse them in their own off-chain tests.
+
+use ink::env::{test, DefaultEnvironment};
+use ink::primitives::AccountId;
+
+type Env = DefaultEnvironment;
+
+/// Default test accounts (alice, bob, charlie, ...)
+pub fn accounts() -> test::DefaultAccounts<Env> {
+    test::default_accounts::<Env>()
+}
+
+/// Set the caller of the next message
+pub fn set_caller(caller: AccountId) {
+    test::set_caller::<Env>(caller);
+}
+
+/// Set the address of the executing contract
+pub fn set_callee(callee: AccountId) {
+    test::set_callee::<Env>(callee);
+}
+
+/// Set the native value transferred with the next message
+pub fn set_value(value: u128) {
+    test::set_value_transferred::<Env>(value);
+}
+
+/// Set the native balance of an account
+pub fn set_balance(account: AccountId, balance: u128) {
+    test::set_account_balance::<Env>(account, balance);
+}
+
+/// Current block number
+pub fn block_number() -> u32 {
+    ink::env::block_number::<Env>()
+}
+
+/// Current block timestamp in milliseconds
+pub fn timestamp() -> u64 {
+    ink::env::block_timestamp::<Env>()
+}
+
+/// Set the current block number
+pub fn set_block_number(block: u32) {
+    test::set_block_number::<Env>(block);
+}
+
+/// Set the current block timestamp in milliseconds
+pub fn set_timestamp(timestamp: u64) {
+    test::set_block_timestamp::<Env>(timestamp);
+}
+
+/// Advance the chain by `blocks` blocks (the timestamp moves with each block)
+pub fn advance_block(blocks: u32) {
+    for _ in 0..blocks {
+        test::advance_block::<Env>();
+    }
+}
+
+/// Move the block timestamp forward by `millis` without producing blocks
+pub fn advance_time(millis: u64) {
+    set_timestamp(timestamp().saturating_add(millis));
+}